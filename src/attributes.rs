@@ -0,0 +1,247 @@
+use eframe::egui::{Button, Checkbox, Grid, Label, ScrollArea, Ui};
+
+use crate::table::{Table, ToTables};
+
+/// Informativeness of one attribute.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Attribute {
+    pub index: usize,
+    /// Share of pairs of classes whose reference vectors differ in the attribute.
+    pub difference: f64,
+    /// Share of realizations agreeing with the reference vector of their class, averaged over classes.
+    pub stability: f64,
+    pub excluded: bool,
+}
+
+impl Attribute {
+    pub fn score(&self) -> f64 {
+        self.difference * self.stability
+    }
+}
+
+/// Attributes ranked by how well they separate classes.
+#[derive(Debug, Default)]
+pub struct AttributeAnalysis {
+    pub attributes: Vec<Attribute>,
+}
+
+impl AttributeAnalysis {
+    /// Analyses binary `matrices` of classes and their `reference_vectors`
+    /// before `excluded` attributes are cleared.
+    pub fn new(
+        matrices: &[Vec<u8>],
+        reference_vectors: &[Vec<u8>],
+        attributes: usize,
+        excluded: &[usize],
+    ) -> Self {
+        let classes = reference_vectors.len();
+        let pairs = classes * classes.saturating_sub(1) / 2;
+
+        let analysis = (0..attributes)
+            .map(|index| {
+                let differing = (0..classes)
+                    .flat_map(|i| (i + 1..classes).map(move |j| (i, j)))
+                    .filter(|&(i, j)| reference_vectors[i][index] != reference_vectors[j][index])
+                    .count();
+
+                let stability = matrices
+                    .iter()
+                    .zip(reference_vectors)
+                    .map(|(matrix, vector)| {
+                        let agreeing = matrix
+                            .chunks(attributes)
+                            .filter(|realization| realization[index] == vector[index])
+                            .count();
+
+                        agreeing as f64 / (matrix.len() / attributes) as f64
+                    })
+                    .sum::<f64>()
+                    / classes as f64;
+
+                Attribute {
+                    index,
+                    difference: if pairs == 0 {
+                        0.0
+                    } else {
+                        differing as f64 / pairs as f64
+                    },
+                    stability,
+                    excluded: excluded.contains(&index),
+                }
+            })
+            .collect();
+
+        Self {
+            attributes: analysis,
+        }
+    }
+
+    /// Attributes from the most to the least informative.
+    pub fn ranked(&self) -> Vec<&Attribute> {
+        let mut ranked: Vec<&Attribute> = self.attributes.iter().collect();
+        ranked.sort_by(|a, b| b.score().total_cmp(&a.score()));
+        ranked
+    }
+
+    /// Attributes equal in reference vectors of all classes.
+    ///
+    /// The best ranked attribute is kept if none differs, so that classes can still be compared.
+    pub fn uninformative(&self) -> Vec<usize> {
+        let mut uninformative: Vec<usize> = self
+            .attributes
+            .iter()
+            .filter(|attribute| attribute.difference == 0.0)
+            .map(|attribute| attribute.index)
+            .collect();
+
+        if uninformative.len() == self.attributes.len() {
+            if let Some(best) = self.ranked().first() {
+                uninformative.retain(|&index| index != best.index);
+            }
+        }
+
+        uninformative
+    }
+
+    /// Shows ranked attributes and returns whether `excluded` attributes changed.
+    pub fn show(&self, excluded: &mut Vec<usize>, ui: &mut Ui) -> bool {
+        let mut changed = false;
+
+        ui.horizontal(|ui| {
+            if ui.add(Button::new("Exclude uninformative")).clicked() {
+                *excluded = self.uninformative();
+                changed = true;
+            }
+            if ui.add(Button::new("Include all")).clicked() {
+                excluded.clear();
+                changed = true;
+            }
+            ui.add(Label::new(format!(
+                "Excluded {} of {}",
+                excluded.len(),
+                self.attributes.len()
+            )));
+        });
+
+        ScrollArea::vertical().show(ui, |ui| {
+            Grid::new("Attributes").striped(true).show(ui, |ui| {
+                [
+                    "Rank",
+                    "Attribute",
+                    "Difference",
+                    "Stability",
+                    "Score",
+                    "Excluded",
+                ]
+                .iter()
+                .for_each(|column| {
+                    ui.add(Label::new(*column));
+                });
+                ui.end_row();
+
+                self.ranked()
+                    .iter()
+                    .enumerate()
+                    .for_each(|(rank, attribute)| {
+                        let mut is_excluded = attribute.excluded;
+
+                        ui.add(Label::new(rank.to_string()));
+                        ui.add(Label::new(attribute.index.to_string()));
+                        ui.add(Label::new(format!("{:.3}", attribute.difference)));
+                        ui.add(Label::new(format!("{:.3}", attribute.stability)));
+                        ui.add(Label::new(format!("{:.3}", attribute.score())));
+                        let last_included =
+                            !is_excluded && excluded.len() + 1 >= self.attributes.len();
+                        if ui
+                            .add_enabled(!last_included, Checkbox::new(&mut is_excluded, ""))
+                            .changed()
+                        {
+                            if is_excluded {
+                                excluded.push(attribute.index);
+                                excluded.sort_unstable();
+                            } else {
+                                excluded.retain(|&index| index != attribute.index);
+                            }
+                            changed = true;
+                        }
+                        ui.end_row();
+                    });
+            });
+        });
+
+        changed
+    }
+}
+
+impl ToTables for AttributeAnalysis {
+    fn tables(&self) -> Vec<Table> {
+        let mut table = Table::new(
+            "attributes",
+            &[
+                "rank",
+                "attribute",
+                "difference",
+                "stability",
+                "score",
+                "excluded",
+            ],
+        );
+
+        self.ranked()
+            .iter()
+            .enumerate()
+            .for_each(|(rank, attribute)| {
+                table.push(vec![
+                    rank.into(),
+                    attribute.index.into(),
+                    attribute.difference.into(),
+                    attribute.stability.into(),
+                    attribute.score().into(),
+                    attribute.excluded.into(),
+                ]);
+            });
+
+        vec![table]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn separating_stable_attributes_rank_first() {
+        const ON: u8 = u8::MAX;
+        const OFF: u8 = u8::MIN;
+
+        // Attribute 0 separates classes, attribute 1 is the same in both, attribute 2 is noisy
+        let matrices = vec![
+            vec![ON, ON, ON, ON, ON, OFF],
+            vec![OFF, ON, OFF, OFF, ON, OFF],
+        ];
+        let reference_vectors = vec![vec![ON, ON, ON], vec![OFF, ON, OFF]];
+
+        let analysis = AttributeAnalysis::new(&matrices, &reference_vectors, 3, &[1]);
+
+        let ranked: Vec<usize> = analysis.ranked().iter().map(|a| a.index).collect();
+        assert_eq!(ranked, [0, 2, 1]);
+        assert_eq!(analysis.attributes[2].stability, 0.75);
+        assert_eq!(analysis.uninformative(), [1]);
+        assert!(analysis.attributes[1].excluded);
+    }
+
+    #[test]
+    fn one_class_keeps_an_attribute() {
+        const ON: u8 = u8::MAX;
+        const OFF: u8 = u8::MIN;
+
+        // A single class has no pairs, so no attribute differs
+        let matrices = vec![vec![ON, OFF, ON, ON, OFF, OFF]];
+        let reference_vectors = vec![vec![ON, OFF, OFF]];
+
+        let analysis = AttributeAnalysis::new(&matrices, &reference_vectors, 3, &[]);
+
+        assert!(analysis.attributes.iter().all(|a| a.difference == 0.0));
+        assert_eq!(analysis.uninformative().len(), 2);
+    }
+}
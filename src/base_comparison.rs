@@ -0,0 +1,166 @@
+use eframe::egui::{Button, Grid, Label, Ui};
+
+use crate::{
+    class_manager::CorridorBase,
+    table::{Table, ToTables},
+};
+
+/// Base of the corridor trained with delta optimization.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BaseCandidate {
+    /// Selected class, the base of [`CorridorBase::SelectedClass`].
+    pub selected_class: usize,
+    pub base: CorridorBase,
+    /// Classes pooled into the base.
+    pub classes: Vec<usize>,
+    /// Best delta, `None` if no delta has working space.
+    pub delta: Option<f64>,
+    /// Value of the objective at the best delta.
+    pub value: Option<f64>,
+}
+
+impl BaseCandidate {
+    pub fn name(&self) -> String {
+        match self.base {
+            CorridorBase::SelectedClass => format!("Class {}", self.selected_class),
+            _ => self.base.to_string(),
+        }
+    }
+}
+
+/// Bases of the corridor compared by the best value of the objective.
+pub struct BaseComparison {
+    pub objective: String,
+    lower_is_better: bool,
+    pub candidates: Vec<BaseCandidate>,
+}
+
+impl BaseComparison {
+    pub fn new(objective: String, lower_is_better: bool, candidates: Vec<BaseCandidate>) -> Self {
+        Self {
+            objective,
+            lower_is_better,
+            candidates,
+        }
+    }
+
+    /// Returns the index of the candidate with the best value of the objective.
+    pub fn best(&self) -> Option<usize> {
+        self.candidates
+            .iter()
+            .enumerate()
+            .filter_map(|(i, candidate)| Some((i, candidate.value?)))
+            .max_by(|(_, a), (_, b)| {
+                if self.lower_is_better {
+                    b.total_cmp(a)
+                } else {
+                    a.total_cmp(b)
+                }
+            })
+            .map(|(i, _)| i)
+    }
+
+    /// Shows the comparison and returns the candidate chosen to be applied.
+    pub fn show(&self, ui: &mut Ui) -> Option<&BaseCandidate> {
+        let best = self.best();
+        let mut chosen = None;
+
+        ui.add(Label::new(format!("Objective: {}", self.objective)));
+
+        Grid::new("Base comparison").striped(true).show(ui, |ui| {
+            ["Base", "Classes", "Delta", "Objective", ""]
+                .iter()
+                .for_each(|column| {
+                    ui.add(Label::new(*column));
+                });
+            ui.end_row();
+
+            self.candidates
+                .iter()
+                .enumerate()
+                .for_each(|(i, candidate)| {
+                    let marker = if best == Some(i) { " ★" } else { "" };
+                    let classes: Vec<String> =
+                        candidate.classes.iter().map(usize::to_string).collect();
+
+                    ui.add(Label::new(format!("{}{marker}", candidate.name())));
+                    ui.add(Label::new(classes.join(", ")));
+                    ui.add(Label::new(
+                        candidate
+                            .delta
+                            .map_or(String::new(), |delta| delta.to_string()),
+                    ));
+                    ui.add(Label::new(
+                        candidate
+                            .value
+                            .map_or("No working space".to_owned(), |value| format!("{value:.4}")),
+                    ));
+                    if ui.add(Button::new("Apply")).clicked() {
+                        chosen = Some(candidate);
+                    }
+                    ui.end_row();
+                });
+        });
+
+        chosen
+    }
+}
+
+impl ToTables for BaseComparison {
+    fn tables(&self) -> Vec<Table> {
+        let best = self.best();
+        let mut table = Table::new(
+            "base_comparison",
+            &["base", "classes", "delta", "objective", "best"],
+        );
+
+        self.candidates
+            .iter()
+            .enumerate()
+            .for_each(|(i, candidate)| {
+                let classes: Vec<String> = candidate.classes.iter().map(usize::to_string).collect();
+
+                table.push(vec![
+                    candidate.name().into(),
+                    classes.join(" ").into(),
+                    candidate.delta.unwrap_or(f64::NAN).into(),
+                    candidate.value.unwrap_or(f64::NAN).into(),
+                    (best == Some(i)).into(),
+                ]);
+            });
+
+        vec![table]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(base: CorridorBase, value: Option<f64>) -> BaseCandidate {
+        BaseCandidate {
+            selected_class: 1,
+            base,
+            classes: vec![1],
+            delta: value.map(|_| 10.0),
+            value,
+        }
+    }
+
+    #[test]
+    fn best_candidate_skips_bases_without_working_space() {
+        let comparison = BaseComparison::new(
+            "Mean Shannon".to_owned(),
+            false,
+            vec![
+                candidate(CorridorBase::SelectedClass, Some(0.2)),
+                candidate(CorridorBase::AllClasses, None),
+                candidate(CorridorBase::Subset(vec![0, 2]), Some(0.4)),
+            ],
+        );
+
+        assert_eq!(comparison.best(), Some(2));
+        assert_eq!(comparison.candidates[0].name(), "Class 1");
+        assert_eq!(comparison.candidates[2].name(), "Classes 0, 2");
+    }
+}
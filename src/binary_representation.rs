@@ -1,107 +1,155 @@
 use eframe::{
-    egui::{Context, Label, Ui},
+    egui::{Context, Label, TextureOptions, Ui},
     epaint::ColorImage,
 };
 
-use crate::{class_data::TextureData, corridor::Allowances, draw::Show};
+use crate::{
+    attributes::AttributeAnalysis,
+    class_data::TextureData,
+    corridor::Allowances,
+    draw::Show,
+    shape::Shape,
+    table::{Table, ToTables},
+};
 
 #[derive(Default)]
 pub struct BinaryRepresentation {
     pub matrices: Vec<TextureData>,
     pub reference_vectors: Vec<TextureData>,
+    /// Informativeness of attributes before exclusion.
+    pub attributes: AttributeAnalysis,
 }
 
 impl BinaryRepresentation {
+    /// Calculates binary matrices and reference vectors of `classes`.
+    ///
+    /// A bit of a reference vector is set if the share of realizations
+    /// with the attribute in allowances exceeds `selection_level`.
+    /// Bits of `excluded` attributes are cleared in matrices and reference vectors.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any class or the allowances do not have `attributes` attributes.
     pub fn new(
         classes: &[TextureData],
-        size: (usize, usize),
+        attributes: usize,
         allowances: &Allowances,
+        selection_level: f64,
+        excluded: &[usize],
         ctx: &Context,
     ) -> Self {
-        let matrices = Self::calculate_binary_matrices(classes, size, allowances, ctx);
-        let reference_vectors = Self::calculate_reference_vectors(&matrices, size, ctx);
+        assert_eq!(allowances.lower.len(), attributes);
+        assert_eq!(allowances.upper.len(), attributes);
+
+        let mut matrices = Self::calculate_binary_matrices(classes, attributes, allowances);
+        let mut reference_vectors =
+            Self::calculate_reference_vectors(&matrices, attributes, selection_level);
+        let analysis = AttributeAnalysis::new(&matrices, &reference_vectors, attributes, excluded);
+
+        matrices
+            .iter_mut()
+            .chain(reference_vectors.iter_mut())
+            .for_each(|bytes| {
+                bytes
+                    .iter_mut()
+                    .enumerate()
+                    .filter(|(i, _)| excluded.contains(&i.rem_euclid(attributes)))
+                    .for_each(|(_, bit)| *bit = u8::MIN);
+            });
 
         Self {
-            matrices,
-            reference_vectors,
+            matrices: matrices
+                .into_iter()
+                .enumerate()
+                .map(|(i, key)| {
+                    let shape = Shape::new(attributes, key.len() / attributes);
+                    let image = ColorImage::from_gray(shape.image_size(), &key);
+                    let texture = ctx.load_texture(
+                        "matrix".to_owned() + &i.to_string(),
+                        image,
+                        TextureOptions::NEAREST,
+                    );
+
+                    TextureData::new(key, texture)
+                })
+                .collect(),
+            reference_vectors: reference_vectors
+                .into_iter()
+                .enumerate()
+                .map(|(i, vector)| {
+                    let image = ColorImage::from_gray([attributes, 10], &vector.repeat(10));
+                    let texture = ctx.load_texture(
+                        "reference_vector".to_owned() + &i.to_string(),
+                        image,
+                        TextureOptions::NEAREST,
+                    );
+
+                    TextureData::new(vector, texture)
+                })
+                .collect(),
+            attributes: analysis,
         }
     }
 
     fn calculate_binary_matrices(
         classes: &[TextureData],
-        size: (usize, usize),
+        attributes: usize,
         allowances: &Allowances,
-        ctx: &Context,
-    ) -> Vec<TextureData> {
-        let (attributes, realizations) = size;
-
+    ) -> Vec<Vec<u8>> {
         classes
             .iter()
-            .enumerate()
-            .map(|(i, class)| {
-                let key: Vec<u8> = class
+            .map(|class| {
+                let shape = class.shape();
+                assert_eq!(shape.attributes, attributes);
+                shape.validate(&class.bytes);
+
+                class
                     .bytes
                     .iter()
                     .enumerate()
                     .map(|(i, x)| {
-                        let index = i.rem_euclid(attributes);
-                        if *x as f64 > allowances.lower[index] && (*x as f64) < allowances.upper[index] {
+                        let index = i.rem_euclid(shape.attributes);
+                        if *x as f64 > allowances.lower[index]
+                            && (*x as f64) < allowances.upper[index]
+                        {
                             u8::MAX
                         } else {
                             u8::MIN
                         }
                     })
-                    .collect();
-
-                let image = ColorImage::from_gray([attributes, realizations], &key);
-                let texture = ctx.load_texture(
-                    "matrix".to_owned() + &i.to_string(),
-                    image,
-                    Default::default(),
-                );
-
-                TextureData::new(key, texture)
+                    .collect()
             })
             .collect()
     }
 
     fn calculate_reference_vectors(
-        matrices: &[TextureData],
-        size: (usize, usize),
-        ctx: &Context,
-    ) -> Vec<TextureData> {
-        let (attributes, realizations) = size;
-
+        matrices: &[Vec<u8>],
+        attributes: usize,
+        selection_level: f64,
+    ) -> Vec<Vec<u8>> {
         matrices
             .iter()
-            .enumerate()
-            .map(|(i, matrix)| {
+            .map(|matrix| {
+                let realizations = matrix.len() / attributes;
                 let mut vector = Vec::with_capacity(attributes);
 
                 for i in 0..attributes {
                     let mut count = 0;
 
                     for j in 0..realizations {
-                        if matrix.bytes[i + j * attributes] == u8::MAX {
+                        if matrix[i + j * attributes] == u8::MAX {
                             count += 1;
                         }
                     }
 
-                    vector.push(if count > realizations / 2 {
+                    vector.push(if count as f64 > realizations as f64 * selection_level {
                         u8::MAX
                     } else {
                         u8::MIN
                     });
                 }
 
-                let image = ColorImage::from_gray([attributes, 10], &vector.repeat(10));
-                let texture = ctx.load_texture(
-                    "reference_vector".to_owned() + &i.to_string(),
-                    image,
-                    Default::default(),
-                );
-
-                TextureData::new(vector, texture)
+                vector
             })
             .collect()
     }
@@ -126,3 +174,150 @@ impl Show for BinaryRepresentation {
         });
     }
 }
+
+impl ToTables for BinaryRepresentation {
+    fn tables(&self) -> Vec<Table> {
+        let bit = |byte: &u8| ((*byte == u8::MAX) as u8).into();
+
+        let mut tables: Vec<Table> =
+            self.matrices
+                .iter()
+                .enumerate()
+                .map(|(i, matrix)| {
+                    let shape = matrix.shape();
+                    let mut table =
+                        Table::indexed(format!("matrix_{i}"), "realization", shape.attributes);
+
+                    matrix.bytes.chunks(shape.attributes).enumerate().for_each(
+                        |(realization, row)| {
+                            table.push(
+                                std::iter::once(realization.into())
+                                    .chain(row.iter().map(bit))
+                                    .collect(),
+                            );
+                        },
+                    );
+
+                    table
+                })
+                .collect();
+
+        let attributes = self.reference_vectors.first().map_or(0, |v| v.bytes.len());
+        let mut reference_vectors = Table::indexed("reference_vectors", "class", attributes);
+
+        self.reference_vectors
+            .iter()
+            .enumerate()
+            .for_each(|(class, vector)| {
+                reference_vectors.push(
+                    std::iter::once(class.into())
+                        .chain(vector.bytes.iter().map(bit))
+                        .collect(),
+                );
+            });
+
+        tables.push(reference_vectors);
+        tables
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{corridor::Corridor, fixtures::load_cropped};
+
+    fn assert_consistent(shape: Shape) {
+        let ctx = Context::default();
+        let classes: Vec<TextureData> = ["1.bmp", "2.bmp", "3.bmp"]
+            .iter()
+            .map(|name| load_cropped(name, shape, &ctx))
+            .collect();
+
+        let mut corridor = Corridor::new(&classes[0].bytes, shape);
+        corridor.delta(20.0);
+
+        let representation = BinaryRepresentation::new(
+            &classes,
+            shape.attributes,
+            &corridor.allowances,
+            0.5,
+            &[],
+            &ctx,
+        );
+
+        for (class, matrix) in classes.iter().zip(&representation.matrices) {
+            assert_eq!(matrix.shape(), shape);
+
+            for realization in 0..shape.realizations {
+                for attribute in 0..shape.attributes {
+                    let index = attribute + realization * shape.attributes;
+                    let value = class.bytes[index] as f64;
+                    let inside = value > corridor.allowances.lower[attribute]
+                        && value < corridor.allowances.upper[attribute];
+
+                    assert_eq!(matrix.bytes[index] == u8::MAX, inside);
+                }
+            }
+        }
+
+        for (matrix, vector) in representation
+            .matrices
+            .iter()
+            .zip(&representation.reference_vectors)
+        {
+            assert_eq!(vector.bytes.len(), shape.attributes);
+
+            for attribute in 0..shape.attributes {
+                let ones = (0..shape.realizations)
+                    .filter(|realization| {
+                        matrix.bytes[attribute + realization * shape.attributes] == u8::MAX
+                    })
+                    .count();
+
+                assert_eq!(
+                    vector.bytes[attribute] == u8::MAX,
+                    ones > shape.realizations / 2
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn wide_classes_are_consistent() {
+        assert_consistent(Shape::new(100, 37));
+    }
+
+    #[test]
+    fn tall_classes_are_consistent() {
+        assert_consistent(Shape::new(37, 100));
+    }
+
+    #[test]
+    fn excluded_attributes_are_cleared() {
+        let ctx = Context::default();
+        let shape = Shape::new(100, 37);
+        let classes = vec![load_cropped("1.bmp", shape, &ctx)];
+        let mut corridor = Corridor::new(&classes[0].bytes, shape);
+        corridor.delta(20.0);
+
+        let representation =
+            BinaryRepresentation::new(&classes, 100, &corridor.allowances, 0.5, &[0, 5], &ctx);
+
+        for realization in representation.matrices[0].bytes.chunks(shape.attributes) {
+            assert_eq!((realization[0], realization[5]), (u8::MIN, u8::MIN));
+        }
+        assert_eq!(representation.reference_vectors[0].bytes[5], u8::MIN);
+        assert!(representation.attributes.attributes[5].stability > 0.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn allowances_not_matching_shape_panic() {
+        let ctx = Context::default();
+        let shape = Shape::new(100, 37);
+        let classes = vec![load_cropped("1.bmp", shape, &ctx)];
+        let corridor = Corridor::new(&classes[0].bytes, shape);
+
+        BinaryRepresentation::new(&classes, 37, &corridor.allowances, 0.5, &[], &ctx);
+    }
+}
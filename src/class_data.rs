@@ -1,13 +1,26 @@
 use eframe::egui::TextureHandle;
 
+use crate::shape::Shape;
+
 #[derive(Clone)]
 pub struct TextureData {
+    /// Name of the class, the name of its texture until renamed.
+    pub name: String,
     pub bytes: Vec<u8>,
     pub texture: TextureHandle,
 }
 
 impl TextureData {
     pub fn new(bytes: Vec<u8>, texture: TextureHandle) -> Self {
-        Self { bytes, texture }
+        Self {
+            name: texture.name(),
+            bytes,
+            texture,
+        }
+    }
+
+    /// Returns the shape of the matrix as defined by its texture.
+    pub fn shape(&self) -> Shape {
+        self.texture.size().into()
     }
 }
@@ -1,67 +1,51 @@
-use crate::class_manager::ClassManager;
+use crate::error::Error;
+use crate::shape::Shape;
 
 use super::class_data::TextureData;
-use eframe::egui::{Button, Context, Label, RadioButton, TextEdit, Ui};
+use eframe::egui::{Button, Context, RadioButton, TextEdit, Ui};
 use eframe::epaint::ColorImage;
-use image::{open, EncodableLayout};
+use image::{open, EncodableLayout, ImageError};
 
-#[derive(PartialEq)]
+#[derive(PartialEq, Default)]
 pub enum ClassType {
+    #[default]
     Training,
     Exam,
 }
 
-impl Default for ClassType {
-    fn default() -> Self {
-        Self::Training
-    }
-}
-
 #[derive(Default)]
 pub struct LoaderResponse {
-    pub loaded: Option<TextureData>,
+    pub loaded: Option<Result<TextureData, Error>>,
 }
 
 impl LoaderResponse {
-    pub fn new(loaded: Option<TextureData>) -> Self {
+    pub fn new(loaded: Option<Result<TextureData, Error>>) -> Self {
         Self { loaded }
     }
 
-    pub fn loaded(&self) -> &Option<TextureData> {
+    pub fn loaded(&self) -> &Option<Result<TextureData, Error>> {
         &self.loaded
     }
 }
 
 #[derive(Default)]
 pub struct ClassLoader {
-    pub size: (usize, usize),
+    /// Shape of the first loaded class, other classes must have as many attributes.
+    pub shape: Shape,
     pub class_type: ClassType,
-    pub error: Option<String>,
     pub path: String,
 }
 
 impl ClassLoader {
-    pub fn show(
-        &mut self,
-        manager: &ClassManager,
-        exam_manager: &ClassManager,
-        ui: &mut Ui,
-    ) -> LoaderResponse {
+    /// Shows the loader, `loaded_classes` is the number of training and exam classes.
+    pub fn show(&mut self, loaded_classes: usize, ui: &mut Ui) -> LoaderResponse {
         let mut response = LoaderResponse::default();
 
         ui.horizontal(|ui| {
             ui.add(TextEdit::singleline(&mut self.path));
 
             if ui.add(Button::new("Load class")).clicked() {
-                match self.load_grayscale(manager, exam_manager, ui.ctx()) {
-                    Err(msg) => {
-                        self.error = Some(msg.to_string());
-                    }
-                    Ok(class) => {
-                        self.error = None;
-                        response = LoaderResponse::new(Some(class));
-                    }
-                }
+                response = LoaderResponse::new(Some(self.load_grayscale(loaded_classes, ui.ctx())));
             };
 
             if ui
@@ -83,50 +67,49 @@ impl ClassLoader {
             {
                 self.class_type = ClassType::Exam;
             }
-
-            if let Some(message) = &self.error {
-                ui.add(Label::new(message));
-
-                if ui.add(Button::new("x")).clicked() {
-                    self.error = None;
-                }
-            }
         });
 
         response
     }
 
-    fn load_grayscale(
+    /// Loads the image at the path as a class, the first of `loaded_classes` sets the shape.
+    pub fn load_grayscale(
         &mut self,
-        class_manager: &ClassManager,
-        exam_manager: &ClassManager,
+        loaded_classes: usize,
         ctx: &Context,
-    ) -> Result<TextureData, String> {
-        let luma = open(&self.path).map_err(|err| err.to_string())?.to_luma8();
+    ) -> Result<TextureData, Error> {
+        let luma = open(&self.path)
+            .map_err(|err| match err {
+                ImageError::IoError(err) => Error::Io {
+                    path: self.path.clone(),
+                    message: err.to_string(),
+                },
+                err => Error::Decode {
+                    path: self.path.clone(),
+                    message: err.to_string(),
+                },
+            })?
+            .to_luma8();
         let vec = luma.to_vec();
 
-        let manager = match self.class_type {
-            ClassType::Training => class_manager,
-            ClassType::Exam => exam_manager,
-        };
+        let shape = Shape::new(luma.width() as usize, luma.height() as usize);
 
-        if manager.classes.iter().any(|x| x.bytes.eq(&vec)) {
-            return Err("This class has already been loaded".to_owned());
+        if shape.is_empty() {
+            return Err(Error::EmptyClass {
+                path: self.path.clone(),
+            });
         }
 
-        if manager.classes.is_empty() {
-            self.size = (luma.width() as usize, luma.height() as usize);
-        } else if self.size != (luma.width() as usize, luma.height() as usize) {
-            return Err(
-                "Error: Classes should have the same number of realizations and attributes"
-                    .to_owned(),
-            );
+        if loaded_classes == 0 {
+            self.shape = shape;
+        } else if self.shape.attributes != shape.attributes {
+            return Err(Error::ShapeMismatch {
+                expected: self.shape.attributes,
+                got: shape.attributes,
+            });
         }
 
-        let image = ColorImage::from_gray(
-            [luma.width() as usize, luma.height() as usize],
-            luma.as_bytes(),
-        );
+        let image = ColorImage::from_gray(shape.image_size(), luma.as_bytes());
 
         let texture = ctx.load_texture(&self.path, image, Default::default());
 
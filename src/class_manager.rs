@@ -1,47 +1,179 @@
-use std::usize;
+use std::fmt::Display;
 
-use eframe::egui::{Context, Label, RadioButton, Ui};
+use eframe::egui::{
+    Button, Checkbox, ComboBox, Context, DragValue, Grid, Label, RadioButton, TextEdit, Ui,
+};
+use eframe::epaint::ColorImage;
 
 use crate::binary_representation::BinaryRepresentation;
 use crate::class_data::TextureData;
 use crate::corridor::Allowances;
 use crate::draw::Show;
+use crate::error::Error;
+use crate::shape::Shape;
 
 #[derive(Default)]
 pub struct ClassManagerControlsResponse {
     changed: bool,
+    deleted: Option<usize>,
 }
 
 impl ClassManagerControlsResponse {
     pub fn new() -> Self {
-        Self { changed: false }
+        Self {
+            changed: false,
+            deleted: None,
+        }
     }
 
     pub fn changed(&self) -> bool {
         self.changed
     }
+
+    /// Returns the class to be removed with [`ClassManager::delete`].
+    pub fn deleted(&self) -> Option<usize> {
+        self.deleted
+    }
+}
+
+/// Classes whose realizations are pooled into the base of the corridor.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub enum CorridorBase {
+    #[default]
+    SelectedClass,
+    AllClasses,
+    /// Chosen classes in ascending order.
+    Subset(Vec<usize>),
+}
+
+impl Display for CorridorBase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CorridorBase::SelectedClass => write!(f, "Selected class"),
+            CorridorBase::AllClasses => write!(f, "All classes"),
+            CorridorBase::Subset(classes) => {
+                let classes: Vec<String> = classes.iter().map(usize::to_string).collect();
+                write!(f, "Classes {}", classes.join(", "))
+            }
+        }
+    }
+}
+
+/// Change of the order or contents of classes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClassOperation {
+    MoveUp(usize),
+    MoveDown(usize),
+    Duplicate(usize),
+    /// Appends realizations of the second class to the first one.
+    Merge(usize, usize),
+    /// Splits the class into realizations before and from the row.
+    Split(usize, usize),
+    /// Moves the class between training and exam classes.
+    Transfer(usize),
 }
 
-#[derive(Default)]
 pub struct ClassManager {
     pub selected_class: usize,
     pub classes: Vec<TextureData>,
     pub binary_representations: BinaryRepresentation,
+    pub corridor_base: CorridorBase,
+    /// Classes chosen to be merged.
+    merged: [usize; 2],
+    /// Row a class is split at.
+    split_row: usize,
+}
+
+impl Default for ClassManager {
+    fn default() -> Self {
+        Self {
+            selected_class: 0,
+            classes: Vec::new(),
+            binary_representations: BinaryRepresentation::default(),
+            corridor_base: CorridorBase::default(),
+            merged: [0; 2],
+            split_row: 1,
+        }
+    }
 }
 
 impl ClassManager {
-    pub fn add_class(&mut self, data: TextureData) {
-        if self.classes.is_empty() || self.classes[0].texture.size() == data.texture.size() {
-            self.classes.push(data);
+    /// Adds the class if it has as many attributes as loaded classes and is not loaded yet,
+    /// the number of realizations may differ.
+    pub fn add_class(&mut self, data: TextureData) -> Result<(), Error> {
+        if let Some(attributes) = self
+            .attributes()
+            .filter(|&attributes| attributes != data.shape().attributes)
+        {
+            return Err(Error::ShapeMismatch {
+                expected: attributes,
+                got: data.shape().attributes,
+            });
+        }
+
+        if self.classes.iter().any(|class| class.bytes == data.bytes) {
+            return Err(Error::DuplicateClass { name: data.name });
         }
+
+        self.classes.push(data);
+
+        Ok(())
     }
 
-    pub fn recalculate_binary_representation(&mut self, allowances: &Allowances, ctx: &Context) {
-        if !self.classes.is_empty() {
+    /// Returns the number of attributes shared by all classes, or `None` if no class is loaded.
+    pub fn attributes(&self) -> Option<usize> {
+        self.classes.first().map(|class| class.shape().attributes)
+    }
+
+    /// Returns the classes pooled into the base of the corridor,
+    /// the selected class if no chosen class is loaded.
+    pub fn base_classes(&self) -> Vec<usize> {
+        let classes: Vec<usize> = match &self.corridor_base {
+            CorridorBase::SelectedClass => vec![self.selected_class],
+            CorridorBase::AllClasses => (0..self.classes.len()).collect(),
+            CorridorBase::Subset(classes) => classes.clone(),
+        };
+        let classes: Vec<usize> = classes
+            .into_iter()
+            .filter(|&class| class < self.classes.len())
+            .collect();
+
+        if classes.is_empty() && self.selected_class < self.classes.len() {
+            vec![self.selected_class]
+        } else {
+            classes
+        }
+    }
+
+    /// Returns realizations of all base classes as one matrix with its shape,
+    /// or `None` if no class is loaded.
+    pub fn base_realizations(&self) -> Option<(Vec<u8>, Shape)> {
+        let attributes = self.attributes()?;
+        let classes = self.base_classes();
+        let bytes: Vec<u8> = classes
+            .iter()
+            .flat_map(|&class| self.classes[class].bytes.iter().copied())
+            .collect();
+
+        let shape = Shape::new(attributes, bytes.len() / attributes);
+
+        Some((bytes, shape))
+    }
+
+    pub fn recalculate_binary_representation(
+        &mut self,
+        allowances: &Allowances,
+        selection_level: f64,
+        excluded: &[usize],
+        ctx: &Context,
+    ) {
+        if let Some(attributes) = self.attributes() {
             self.binary_representations = BinaryRepresentation::new(
                 &self.classes,
-                self.classes[0].texture.size().into(),
+                attributes,
                 allowances,
+                selection_level,
+                excluded,
                 ctx,
             );
         }
@@ -74,17 +206,248 @@ impl ClassManager {
             });
         });
 
-        if ui.button("Delete").clicked() {
-            if self.classes.len() > self.selected_class {
-                self.classes.remove(self.selected_class);
-            }
+        response.changed |= self.show_corridor_base(ui);
 
+        if ui.button("Delete").clicked() {
+            response.deleted = Some(self.selected_class);
             response.changed = true;
-            self.selected_class = 0;
         }
 
         response
     }
+
+    /// Removes the class and selects the first one.
+    pub fn delete(&mut self, class: usize) {
+        if class < self.classes.len() {
+            self.classes.remove(class);
+            self.forget_class(class);
+        }
+
+        self.selected_class = 0;
+    }
+
+    /// Shows the choice of the corridor base and returns `true` if it has changed.
+    fn show_corridor_base(&mut self, ui: &mut Ui) -> bool {
+        let mut changed = false;
+        let subset = match &self.corridor_base {
+            CorridorBase::Subset(classes) => classes.clone(),
+            _ => vec![self.selected_class],
+        };
+
+        ui.horizontal(|ui| {
+            ui.add(Label::new("Corridor base:"));
+            [
+                CorridorBase::SelectedClass,
+                CorridorBase::AllClasses,
+                CorridorBase::Subset(subset),
+            ]
+            .into_iter()
+            .for_each(|base| {
+                let selected =
+                    std::mem::discriminant(&self.corridor_base) == std::mem::discriminant(&base);
+                let label = match base {
+                    CorridorBase::Subset(_) => "Chosen classes".to_owned(),
+                    _ => base.to_string(),
+                };
+
+                if ui.add(RadioButton::new(selected, label)).clicked() && !selected {
+                    self.corridor_base = base;
+                    changed = true;
+                }
+            });
+        });
+
+        if let CorridorBase::Subset(classes) = &mut self.corridor_base {
+            ui.horizontal_wrapped(|ui| {
+                (0..self.classes.len()).for_each(|i| {
+                    let mut chosen = classes.contains(&i);
+
+                    if ui.add(Checkbox::new(&mut chosen, i.to_string())).changed() {
+                        if chosen {
+                            classes.push(i);
+                            classes.sort_unstable();
+                        } else {
+                            classes.retain(|&class| class != i);
+                        }
+                        changed = true;
+                    }
+                });
+            });
+        }
+
+        changed
+    }
+
+    /// Applies the operation and returns the indices of the old classes every class came from,
+    /// `None` if the operation changes nothing.
+    ///
+    /// The selected class and the chosen classes of the corridor base follow their classes.
+    /// [`ClassOperation::Transfer`] removes the class, the caller adds it to the other manager.
+    pub fn apply(&mut self, operation: ClassOperation, ctx: &Context) -> Option<Vec<Vec<usize>>> {
+        let count = self.classes.len();
+        let mut sources: Vec<Vec<usize>> = (0..count).map(|i| vec![i]).collect();
+        let mut classes = self.classes.clone();
+
+        match operation {
+            ClassOperation::MoveUp(i) if i > 0 && i < count => {
+                sources.swap(i - 1, i);
+                classes.swap(i - 1, i);
+            }
+            ClassOperation::MoveDown(i) if i + 1 < count => {
+                sources.swap(i, i + 1);
+                classes.swap(i, i + 1);
+            }
+            ClassOperation::Duplicate(i) if i < count => {
+                sources.insert(i + 1, vec![i]);
+                classes.insert(i + 1, self.classes[i].clone());
+            }
+            ClassOperation::Merge(i, j) if i != j && i < count && j < count => {
+                let first = &self.classes[i];
+                let second = &self.classes[j];
+                let bytes = [first.bytes.as_slice(), second.bytes.as_slice()].concat();
+                let name = format!("{} + {}", first.name, second.name);
+
+                classes[i] = texture(name, bytes, first.shape().attributes, ctx);
+                sources[i].push(j);
+                classes.remove(j);
+                sources.remove(j);
+            }
+            ClassOperation::Split(i, row) if i < count => {
+                let class = &self.classes[i];
+                let shape = class.shape();
+                if row == 0 || row >= shape.realizations {
+                    return None;
+                }
+
+                let (first, second) = class.bytes.split_at(row * shape.attributes);
+                let parts = [(first, "1"), (second, "2")].map(|(bytes, part)| {
+                    let name = format!("{} ({part})", class.name);
+                    texture(name, bytes.to_vec(), shape.attributes, ctx)
+                });
+
+                classes.splice(i..=i, parts);
+                sources.insert(i + 1, vec![i]);
+            }
+            ClassOperation::Transfer(i) if i < count => {
+                classes.remove(i);
+                sources.remove(i);
+            }
+            _ => return None,
+        }
+
+        self.classes = classes;
+        self.selected_class = sources
+            .iter()
+            .position(|sources| sources.contains(&self.selected_class))
+            .unwrap_or_default();
+        if let CorridorBase::Subset(chosen) = &mut self.corridor_base {
+            *chosen = sources
+                .iter()
+                .enumerate()
+                .filter(|(_, sources)| sources.iter().any(|class| chosen.contains(class)))
+                .map(|(i, _)| i)
+                .collect();
+        }
+
+        Some(sources)
+    }
+
+    /// Returns pairs of classes with the same realizations, which no radius tells apart.
+    pub fn identical(&self) -> Vec<(usize, usize)> {
+        (0..self.classes.len())
+            .flat_map(|i| (i + 1..self.classes.len()).map(move |j| (i, j)))
+            .filter(|&(i, j)| self.classes[i].bytes == self.classes[j].bytes)
+            .collect()
+    }
+
+    /// Shows names of classes with operations on them and returns the requested operation.
+    /// `other` names the manager classes are transferred to.
+    pub fn show_operations(&mut self, other: &str, ui: &mut Ui) -> Option<ClassOperation> {
+        let mut operation = None;
+        let count = self.classes.len();
+
+        Grid::new(ui.id().with("Class operations")).show(ui, |ui| {
+            self.classes.iter_mut().enumerate().for_each(|(i, class)| {
+                ui.add(Label::new(i.to_string()));
+                ui.add(TextEdit::singleline(&mut class.name).desired_width(120.0));
+                if ui.add_enabled(i > 0, Button::new("⬆")).clicked() {
+                    operation = Some(ClassOperation::MoveUp(i));
+                }
+                if ui.add_enabled(i + 1 < count, Button::new("⬇")).clicked() {
+                    operation = Some(ClassOperation::MoveDown(i));
+                }
+                if ui
+                    .add(Button::new("Duplicate"))
+                    .on_hover_text("The copy is identical to the class until it is split or merged")
+                    .clicked()
+                {
+                    operation = Some(ClassOperation::Duplicate(i));
+                }
+                if ui.add(Button::new("Split")).clicked() {
+                    operation = Some(ClassOperation::Split(i, self.split_row));
+                }
+                if ui.add(Button::new(format!("To {other}"))).clicked() {
+                    operation = Some(ClassOperation::Transfer(i));
+                }
+                ui.end_row();
+            });
+        });
+
+        let realizations = self
+            .classes
+            .iter()
+            .map(|class| class.shape().realizations)
+            .max()
+            .unwrap_or_default();
+        ui.horizontal(|ui| {
+            ui.add(Label::new("Split at realization"));
+            ui.add(DragValue::new(&mut self.split_row).clamp_range(1..=realizations.max(2) - 1));
+        });
+
+        ui.horizontal(|ui| {
+            ui.add(Label::new("Merge"));
+            self.merged.iter_mut().enumerate().for_each(|(k, class)| {
+                ComboBox::from_id_source(ui.id().with(("Merged", k)))
+                    .selected_text(class.to_string())
+                    .show_ui(ui, |ui| {
+                        (0..count).for_each(|i| {
+                            ui.selectable_value(class, i, i.to_string());
+                        });
+                    });
+            });
+
+            let [first, second] = self.merged;
+            if ui
+                .add_enabled(first != second, Button::new("Merge"))
+                .clicked()
+            {
+                operation = Some(ClassOperation::Merge(first, second));
+            }
+        });
+
+        operation
+    }
+
+    /// Removes a deleted class from the chosen classes and shifts the following ones.
+    fn forget_class(&mut self, deleted: usize) {
+        if let CorridorBase::Subset(classes) = &mut self.corridor_base {
+            classes.retain(|&class| class != deleted);
+            classes
+                .iter_mut()
+                .filter(|class| **class > deleted)
+                .for_each(|class| *class -= 1);
+        }
+    }
+}
+
+/// Creates a class of `attributes` columns from its bytes.
+fn texture(name: String, bytes: Vec<u8>, attributes: usize, ctx: &Context) -> TextureData {
+    let shape = Shape::new(attributes, bytes.len() / attributes);
+    let image = ColorImage::from_gray(shape.image_size(), &bytes);
+    let mut class = TextureData::new(bytes, ctx.load_texture(&name, image, Default::default()));
+    class.name = name;
+
+    class
 }
 
 impl Show for ClassManager {
@@ -93,8 +456,98 @@ impl Show for ClassManager {
 
         ui.horizontal(|ui| {
             self.classes.iter().for_each(|class| {
-                ui.image((class.texture.id(), class.texture.size_vec2()));
+                ui.image((class.texture.id(), class.texture.size_vec2()))
+                    .on_hover_text(&class.name);
             });
         });
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures;
+
+    #[test]
+    fn base_realizations_pool_chosen_classes() {
+        let ctx = Context::default();
+        let shape = Shape::new(4, 2);
+        let mut class_manager = ClassManager::default();
+        ["1.bmp", "2.bmp", "3.bmp"].iter().for_each(|name| {
+            class_manager
+                .add_class(fixtures::load_cropped(name, shape, &ctx))
+                .unwrap();
+        });
+        class_manager.selected_class = 1;
+
+        let (bytes, pooled) = class_manager.base_realizations().unwrap();
+        assert_eq!(bytes, class_manager.classes[1].bytes);
+        assert_eq!(pooled, shape);
+
+        class_manager.corridor_base = CorridorBase::Subset(vec![0, 2]);
+        let (bytes, pooled) = class_manager.base_realizations().unwrap();
+        assert_eq!(bytes[..8], class_manager.classes[0].bytes);
+        assert_eq!(bytes[8..], class_manager.classes[2].bytes);
+        assert_eq!(pooled, Shape::new(4, 4));
+
+        class_manager.forget_class(0);
+        assert_eq!(class_manager.corridor_base, CorridorBase::Subset(vec![1]));
+    }
+
+    #[test]
+    fn operations_keep_selected_and_chosen_classes() {
+        let ctx = Context::default();
+        let shape = Shape::new(4, 2);
+        let mut class_manager = ClassManager::default();
+        ["1.bmp", "2.bmp", "3.bmp"].iter().for_each(|name| {
+            class_manager
+                .add_class(fixtures::load_cropped(name, shape, &ctx))
+                .unwrap();
+        });
+        let bytes: Vec<Vec<u8>> = class_manager
+            .classes
+            .iter()
+            .map(|class| class.bytes.clone())
+            .collect();
+        class_manager.selected_class = 2;
+        class_manager.corridor_base = CorridorBase::Subset(vec![0, 2]);
+
+        let sources = class_manager.apply(ClassOperation::MoveUp(2), &ctx);
+        assert_eq!(sources, Some(vec![vec![0], vec![2], vec![1]]));
+        assert_eq!(class_manager.selected_class, 1);
+        assert_eq!(
+            class_manager.corridor_base,
+            CorridorBase::Subset(vec![0, 1])
+        );
+
+        let sources = class_manager.apply(ClassOperation::Merge(0, 2), &ctx);
+        assert_eq!(sources, Some(vec![vec![0, 2], vec![1]]));
+        assert_eq!(
+            class_manager.classes[0].bytes,
+            [&bytes[0][..], &bytes[1][..]].concat()
+        );
+        assert_eq!(class_manager.classes[0].shape(), Shape::new(4, 4));
+        assert_eq!(class_manager.classes[0].name, "1.bmp + 2.bmp");
+
+        let sources = class_manager.apply(ClassOperation::Split(0, 1), &ctx);
+        assert_eq!(sources, Some(vec![vec![0], vec![0], vec![1]]));
+        assert_eq!(
+            class_manager.classes[1].bytes,
+            [&bytes[0][4..], &bytes[1][..]].concat()
+        );
+
+        assert_eq!(class_manager.apply(ClassOperation::Split(0, 0), &ctx), None);
+        assert_eq!(class_manager.apply(ClassOperation::Split(0, 1), &ctx), None);
+        assert_eq!(class_manager.apply(ClassOperation::Merge(1, 1), &ctx), None);
+
+        class_manager.apply(ClassOperation::Duplicate(2), &ctx);
+        assert_eq!(class_manager.identical(), [(2, 3)]);
+        class_manager.apply(ClassOperation::Transfer(0), &ctx);
+        assert_eq!(class_manager.classes.len(), 3);
+        assert_eq!(class_manager.selected_class, 1);
+        assert_eq!(
+            class_manager.corridor_base,
+            CorridorBase::Subset(vec![0, 1, 2])
+        );
+    }
+}
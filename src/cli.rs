@@ -0,0 +1,824 @@
+use std::path::{Path, PathBuf};
+
+use eframe::egui::Context;
+
+use crate::{
+    class_loader::ClassType,
+    class_manager::CorridorBase,
+    corridor::Statistics,
+    criterion::CriterionKind,
+    error::Error,
+    figure::ToFigure,
+    optimizer::{OptimizationMode, Parameter, ParameterRange, Strategy},
+    table::{self, DataFormat},
+    MyApp,
+};
+
+const USAGE: &str = "\
+Usage: tm [OPTIONS] <TRAINING>...
+
+Trains on the given bmp classes without opening a window.
+
+Options:
+  --exam <PATH>     Load an exam class, can be repeated
+  --base <CLASS|all|CLASS,...>
+                    Base class of the corridor, all training classes or
+                    the listed classes pooled together [default: 0]
+  --compare-bases   Optimize delta with the base class, all classes and
+                    the listed classes as the corridor base and compare them
+  --auto-base       Optimize delta with every class as the base and use the
+                    class with the best criterion averaged over classes
+  --delta <DELTA>   Delta of the corridor [default: 0]
+  --centre <CENTRE> Centre of the corridor: mean, median, trimmed_mean or mode
+                    [default: mean]
+  --trim <SHARE>    Share of realizations cut from each end by trimmed_mean
+                    [default: 0.1]
+  --tolerance <TOLERANCE>
+                    Units of delta: absolute, percent_of_expectation,
+                    percent_of_std_dev or percent_of_quantiles [default: absolute]
+  --quantile <LEVEL>
+                    Lower quantile of percent_of_quantiles, the upper one is
+                    1 - LEVEL [default: 0.1]
+  --exclude <ATTRIBUTE,...>
+                    Attributes skipped by training and exam
+  --optimize        Optimize delta instead of using --delta
+  --delta-range <MIN:MAX:STEP>
+                    Deltas searched by --optimize [default: 0:255:1]
+  --search <STRATEGY>
+                    Optimize --parameter values by grid, golden_section,
+                    coordinate_descent or random search
+  --parameter <NAME[=MIN:MAX:STEP]>
+                    Parameter searched by --search, can be repeated:
+                    delta, delta_<ATTRIBUTE> or selection_level [default: delta]
+  --radius-criterion <CRITERION>
+                    Criterion selecting radii of containers [default: kullback]
+  --objective <CRITERION>
+                    Criterion maximized by --optimize [default: shannon]
+  --mode <MODE>     Optimize for the base class against its closest neighbour
+                    (selected_class), all classes on average (average) or
+                    the worst class (maximin) [default: average]
+  --priors <W,...>  Prior weights of training classes [default: equal]
+  --cost-ratio <R>  Cost of alpha relative to beta [default: 1]
+  --plots <DIR>     Export all plots to DIR as SVG and PNG
+  --size <WxH>      Size of exported plots in pixels [default: 1200x800]
+  --data <DIR>      Export all numeric results to DIR
+  --format <FORMAT> Format of numeric results, csv or json [default: csv]
+  --help            Print this message
+
+Criteria: kullback, normalized_kullback, shannon, total_error, youden_index,
+f_measure, mutual_information
+
+Exit status:
+  0  Success
+  2  Invalid arguments
+  3  A file could not be read or written
+  4  A file is not a supported image
+  5  A class is empty, duplicated or has a different number of attributes
+  6  No training classes
+  7  No parameter values give a working space";
+
+#[derive(Debug, PartialEq)]
+pub struct Args {
+    pub training: Vec<String>,
+    pub exam: Vec<String>,
+    pub base: usize,
+    pub corridor_base: CorridorBase,
+    pub compare_bases: bool,
+    pub auto_base: bool,
+    pub delta: f64,
+    pub statistics: Statistics,
+    pub excluded: Vec<usize>,
+    pub optimize: bool,
+    pub delta_range: Option<ParameterRange>,
+    pub search: Option<Strategy>,
+    pub parameters: Vec<ParameterRange>,
+    pub radius_criterion: CriterionKind,
+    pub objective: CriterionKind,
+    pub mode: OptimizationMode,
+    pub priors: Vec<f64>,
+    pub cost_ratio: f64,
+    pub plots: Option<PathBuf>,
+    pub size: (u32, u32),
+    pub data: Option<PathBuf>,
+    pub format: DataFormat,
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Self {
+            training: Vec::new(),
+            exam: Vec::new(),
+            base: 0,
+            corridor_base: CorridorBase::SelectedClass,
+            compare_bases: false,
+            auto_base: false,
+            delta: 0.0,
+            statistics: Statistics::default(),
+            excluded: Vec::new(),
+            optimize: false,
+            delta_range: None,
+            search: None,
+            parameters: Vec::new(),
+            radius_criterion: CriterionKind::Kullback,
+            objective: CriterionKind::Shannon,
+            mode: OptimizationMode::Average,
+            priors: Vec::new(),
+            cost_ratio: 1.0,
+            plots: None,
+            size: (1200, 800),
+            data: None,
+            format: DataFormat::Csv,
+        }
+    }
+}
+
+impl Args {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, Error> {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| {
+                args.next()
+                    .ok_or_else(|| Error::InvalidArgument(format!("Missing value of {name}")))
+            };
+
+            match arg.as_str() {
+                "--exam" => parsed.exam.push(value("--exam")?),
+                "--base" => (parsed.base, parsed.corridor_base) = parse_base(&value("--base")?)?,
+                "--compare-bases" => parsed.compare_bases = true,
+                "--auto-base" => parsed.auto_base = true,
+                "--delta" => parsed.delta = parse_delta(&value("--delta")?)?,
+                "--centre" => parsed.statistics.centre = value("--centre")?.parse()?,
+                "--trim" => parsed.statistics.trim = parse_share(&value("--trim")?, 0.5)?,
+                "--tolerance" => parsed.statistics.tolerance = value("--tolerance")?.parse()?,
+                "--quantile" => {
+                    parsed.statistics.quantile = parse_share(&value("--quantile")?, 0.5)?
+                }
+                "--exclude" => {
+                    parsed.excluded = value("--exclude")?
+                        .split(',')
+                        .map(parse_number)
+                        .collect::<Result<_, _>>()?;
+                    parsed.excluded.sort_unstable();
+                    parsed.excluded.dedup();
+                }
+                "--optimize" => parsed.optimize = true,
+                "--delta-range" => {
+                    parsed.delta_range =
+                        Some(parse_range(&format!("delta={}", value("--delta-range")?))?)
+                }
+                "--search" => parsed.search = Some(value("--search")?.parse()?),
+                "--parameter" => parsed.parameters.push(parse_range(&value("--parameter")?)?),
+                "--radius-criterion" => {
+                    parsed.radius_criterion = value("--radius-criterion")?.parse()?
+                }
+                "--objective" => parsed.objective = value("--objective")?.parse()?,
+                "--mode" => parsed.mode = value("--mode")?.parse()?,
+                "--priors" => parsed.priors = parse_priors(&value("--priors")?)?,
+                "--cost-ratio" => parsed.cost_ratio = parse_cost_ratio(&value("--cost-ratio")?)?,
+                "--plots" => parsed.plots = Some(value("--plots")?.into()),
+                "--size" => parsed.size = parse_size(&value("--size")?)?,
+                "--data" => parsed.data = Some(value("--data")?.into()),
+                "--format" => parsed.format = value("--format")?.parse()?,
+                _ if arg.starts_with("--") => {
+                    return Err(Error::InvalidArgument(format!(
+                        "Unknown option {arg}\n\n{USAGE}"
+                    )))
+                }
+                _ => parsed.training.push(arg),
+            }
+        }
+
+        if !parsed.priors.is_empty() && parsed.priors.len() != parsed.training.len() {
+            return Err(Error::InvalidArgument(format!(
+                "Expected {} prior weights, got {}",
+                parsed.training.len(),
+                parsed.priors.len()
+            )));
+        }
+
+        Ok(parsed)
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str) -> Result<T, Error> {
+    value
+        .parse()
+        .map_err(|_| Error::InvalidArgument(format!("Invalid number {value}")))
+}
+
+fn parse_delta(value: &str) -> Result<f64, Error> {
+    let delta: f64 = parse_number(value)?;
+
+    if delta.is_finite() && delta >= 0.0 {
+        Ok(delta)
+    } else {
+        Err(Error::InvalidArgument(format!(
+            "Invalid delta {value}, expected a non-negative number"
+        )))
+    }
+}
+
+/// Parses relative prior weights of training classes.
+fn parse_priors(value: &str) -> Result<Vec<f64>, Error> {
+    let priors: Vec<f64> = value
+        .split(',')
+        .map(parse_number)
+        .collect::<Result<_, _>>()?;
+
+    if priors
+        .iter()
+        .all(|prior| prior.is_finite() && *prior >= 0.0)
+        && priors.iter().sum::<f64>() > 0.0
+    {
+        Ok(priors)
+    } else {
+        Err(Error::InvalidArgument(format!(
+            "Invalid prior weights {value}, expected non-negative numbers with a positive sum"
+        )))
+    }
+}
+
+fn parse_cost_ratio(value: &str) -> Result<f64, Error> {
+    let ratio: f64 = parse_number(value)?;
+
+    if ratio.is_finite() && ratio > 0.0 {
+        Ok(ratio)
+    } else {
+        Err(Error::InvalidArgument(format!(
+            "Invalid cost ratio {value}, expected a positive number"
+        )))
+    }
+}
+
+/// Parses the selected class and the classes pooled into the corridor base.
+fn parse_base(value: &str) -> Result<(usize, CorridorBase), Error> {
+    if value == "all" {
+        return Ok((0, CorridorBase::AllClasses));
+    }
+
+    let mut classes: Vec<usize> = value
+        .split(',')
+        .map(parse_number)
+        .collect::<Result<_, _>>()?;
+    let selected = classes[0];
+    classes.sort_unstable();
+    classes.dedup();
+
+    if classes.len() == 1 {
+        Ok((selected, CorridorBase::SelectedClass))
+    } else {
+        Ok((selected, CorridorBase::Subset(classes)))
+    }
+}
+
+/// Parses a share of realizations below `max`.
+fn parse_share(value: &str, max: f64) -> Result<f64, Error> {
+    let share: f64 = parse_number(value)?;
+
+    if (0.0..max).contains(&share) {
+        Ok(share)
+    } else {
+        Err(Error::InvalidArgument(format!(
+            "Invalid share {value}, expected at least 0 and below {max}"
+        )))
+    }
+}
+
+fn parse_range(value: &str) -> Result<ParameterRange, Error> {
+    let (name, range) = match value.split_once('=') {
+        Some((name, range)) => (name, Some(range)),
+        None => (value, None),
+    };
+    let parameter: Parameter = name.parse()?;
+
+    let Some(range) = range else {
+        return Ok(ParameterRange::of(parameter));
+    };
+
+    match range.split(':').collect::<Vec<&str>>()[..] {
+        [min, max, step] => Ok(ParameterRange::new(
+            parameter,
+            parse_number(min)?,
+            parse_number(max)?,
+            parse_number(step)?,
+        )),
+        _ => Err(Error::InvalidArgument(format!(
+            "Invalid range {range}, expected MIN:MAX:STEP"
+        ))),
+    }
+}
+
+fn parse_size(value: &str) -> Result<(u32, u32), Error> {
+    let (width, height) = value
+        .split_once('x')
+        .ok_or_else(|| Error::InvalidArgument(format!("Invalid size {value}, expected WxH")))?;
+
+    Ok((parse_number(width)?, parse_number(height)?))
+}
+
+/// Attaches to the console of the parent process, which release builds on Windows
+/// do not get, so that output and errors reach the terminal the command was run from.
+#[cfg(windows)]
+pub fn attach_console() {
+    const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn AttachConsole(process_id: u32) -> i32;
+    }
+
+    // Fails if there is no parent console or the process already has one, both are fine
+    unsafe {
+        AttachConsole(ATTACH_PARENT_PROCESS);
+    }
+}
+
+#[cfg(not(windows))]
+pub fn attach_console() {}
+
+/// Runs training from command line arguments and returns the exit code.
+pub fn run(args: impl IntoIterator<Item = String>) -> i32 {
+    let args: Vec<String> = args.into_iter().collect();
+
+    if args.iter().any(|arg| arg == "--help") {
+        println!("{USAGE}");
+        return 0;
+    }
+
+    match Args::parse(args).and_then(|args| train(&args)) {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("{err}");
+            err.exit_code()
+        }
+    }
+}
+
+fn train(args: &Args) -> Result<(), Error> {
+    let ctx = Context::default();
+    let mut app = MyApp::default();
+    app.settings.radius_criterion = args.radius_criterion;
+    app.settings.objective = args.objective;
+    app.settings.optimization_mode = args.mode;
+    app.settings.prior_weights = args.priors.clone();
+    app.settings.cost_ratio = args.cost_ratio;
+    app.settings.statistics = args.statistics;
+    app.settings.excluded_attributes = args.excluded.clone();
+    if let Some(range) = &args.delta_range {
+        app.settings.delta_range = range.clone();
+    }
+
+    load(&mut app, &args.training, ClassType::Training, &ctx)?;
+    load(&mut app, &args.exam, ClassType::Exam, &ctx)?;
+
+    if app.class_manager.classes.is_empty() {
+        return Err(Error::EmptyTrainingSet);
+    }
+
+    if let Some(attribute) = args
+        .excluded
+        .iter()
+        .find(|&&attribute| attribute >= app.class_loader.shape.attributes)
+    {
+        return Err(Error::InvalidArgument(format!(
+            "No attribute {attribute} to exclude"
+        )));
+    }
+    if args.excluded.len() >= app.class_loader.shape.attributes {
+        return Err(Error::InvalidArgument(
+            "Cannot exclude every attribute".to_owned(),
+        ));
+    }
+
+    let classes = match &args.corridor_base {
+        CorridorBase::Subset(classes) => classes.clone(),
+        _ => vec![args.base],
+    };
+    if let Some(class) = classes
+        .iter()
+        .find(|&&class| class >= app.class_manager.classes.len())
+    {
+        return Err(Error::InvalidArgument(format!(
+            "No class {class} to use as base"
+        )));
+    }
+
+    app.class_manager.corridor_base = args.corridor_base.clone();
+    app.set_base_class(args.base, &ctx);
+
+    if args.auto_base {
+        app.auto_base_class(&ctx);
+    } else if args.compare_bases {
+        app.compare_corridor_bases(&ctx);
+    }
+
+    if let Some(strategy) = args.search {
+        app.optimizer.strategy = strategy;
+        if !args.parameters.is_empty() {
+            app.optimizer.space.ranges = args.parameters.clone();
+        }
+        app.optimize_parameters(&ctx)?;
+    } else if args.optimize {
+        app.optimize(&ctx)?;
+    } else if !args.auto_base {
+        app.delta = args.delta;
+        app.corridor.delta(app.delta);
+        app.recalculate(&ctx);
+    }
+
+    if args.auto_base {
+        println!("Base class: {}", app.class_manager.selected_class);
+    }
+    println!("Delta: {}", app.delta);
+    if let Some(best) = app.optimization_log.as_ref().and_then(|log| log.best()) {
+        app.optimizer
+            .space
+            .parameters()
+            .iter()
+            .zip(&best.values)
+            .for_each(|(parameter, value)| println!("{parameter}: {value}"));
+    }
+    if let Some(base_comparison) = &app.base_comparison {
+        base_comparison.candidates.iter().for_each(|candidate| {
+            println!(
+                "Base {}: delta {}, {} {}",
+                candidate.name(),
+                candidate
+                    .delta
+                    .map_or("none".to_owned(), |delta| delta.to_string()),
+                base_comparison.objective,
+                candidate
+                    .value
+                    .map_or("none".to_owned(), |value| format!("{value:.4}"))
+            )
+        });
+    }
+    app.exam_data
+        .iter()
+        .enumerate()
+        .for_each(|(i, exam_result)| println!("Exam result for {i}: {exam_result}"));
+    app.notifications
+        .warnings()
+        .iter()
+        .for_each(|warning| eprintln!("{warning}"));
+
+    if let Some(dir) = &args.plots {
+        export_plots(&app, dir, args.size)?;
+    }
+
+    if let Some(dir) = &args.data {
+        table::save(&app.tables(), dir, args.format)?;
+    }
+
+    Ok(())
+}
+
+fn load(
+    app: &mut MyApp,
+    paths: &[String],
+    class_type: ClassType,
+    ctx: &Context,
+) -> Result<(), Error> {
+    app.class_loader.class_type = class_type;
+
+    paths.iter().try_for_each(|path| {
+        app.class_loader.path = path.clone();
+        let loaded_classes = app.class_manager.classes.len() + app.exam_class_manager.classes.len();
+        let data = app.class_loader.load_grayscale(loaded_classes, ctx)?;
+
+        app.add_class(data, ctx)
+    })
+}
+
+fn export_plots(app: &MyApp, dir: &Path, (width, height): (u32, u32)) -> Result<(), Error> {
+    std::fs::create_dir_all(dir)
+        .map_err(|err| Error::Export(format!("{}: {err}", dir.display())))?;
+
+    let mut figures = vec![("corridor".to_owned(), app.corridor.figure())];
+
+    figures.extend(
+        app.criterias
+            .iter()
+            .enumerate()
+            .map(|(i, criteria)| (format!("criteria_{i}"), criteria.figure())),
+    );
+
+    figures.extend(
+        app.sk_manager
+            .sk
+            .iter()
+            .enumerate()
+            .map(|(i, sk)| (format!("2d_{i}"), sk.figure())),
+    );
+
+    figures.extend(
+        app.histograms
+            .iter()
+            .map(|histogram| (format!("histogram_{}", histogram.class), histogram.figure())),
+    );
+
+    figures.push(("projection".to_owned(), app.projection.figure()));
+
+    if let Some(optimization_results) = &app.optimization_results {
+        figures.push(("optimization".to_owned(), optimization_results.figure()));
+    }
+
+    if let Some(optimization_log) = &app.optimization_log {
+        figures.push(("optimization_log".to_owned(), optimization_log.figure()));
+    }
+
+    figures.iter().try_for_each(|(name, figure)| {
+        ["svg", "png"].iter().try_for_each(|extension| {
+            figure.save(&dir.join(format!("{name}.{extension}")), width, height)
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        corridor::{Centre, Tolerance},
+        fixtures,
+    };
+
+    fn args(args: &[&str]) -> Result<Args, Error> {
+        Args::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn options_are_parsed() {
+        assert_eq!(
+            args(&[
+                "1.bmp",
+                "--exam",
+                "exm.bmp",
+                "2.bmp",
+                "--base",
+                "1",
+                "--delta",
+                "40",
+                "--centre",
+                "median",
+                "--tolerance",
+                "percent_of_quantiles",
+                "--quantile",
+                "0.25",
+                "--delta-range",
+                "0:50:0.5",
+                "--exclude",
+                "7,3,7",
+                "--plots",
+                "out",
+                "--size",
+                "640x480",
+                "--objective",
+                "youden_index",
+                "--mode",
+                "maximin",
+                "--priors",
+                "1,3",
+                "--cost-ratio",
+                "2.5",
+            ]),
+            Ok(Args {
+                training: vec!["1.bmp".to_owned(), "2.bmp".to_owned()],
+                exam: vec!["exm.bmp".to_owned()],
+                base: 1,
+                corridor_base: CorridorBase::SelectedClass,
+                compare_bases: false,
+                auto_base: false,
+                delta: 40.0,
+                statistics: Statistics {
+                    centre: Centre::Median,
+                    tolerance: Tolerance::PercentOfQuantiles,
+                    quantile: 0.25,
+                    ..Default::default()
+                },
+                excluded: vec![3, 7],
+                optimize: false,
+                delta_range: Some(ParameterRange::new(Parameter::Delta, 0.0, 50.0, 0.5)),
+                search: None,
+                parameters: Vec::new(),
+                radius_criterion: CriterionKind::Kullback,
+                objective: CriterionKind::Youden,
+                mode: OptimizationMode::Maximin,
+                priors: vec![1.0, 3.0],
+                cost_ratio: 2.5,
+                plots: Some("out".into()),
+                size: (640, 480),
+                data: None,
+                format: DataFormat::Csv,
+            })
+        );
+    }
+
+    #[test]
+    fn invalid_arguments_are_rejected() {
+        assert!(args(&["1.bmp", "--delta"]).is_err());
+        assert!(args(&["1.bmp", "--delta", "-1"]).is_err());
+        assert!(args(&["1.bmp", "--tolerance", "relative"]).is_err());
+        assert!(args(&["1.bmp", "--centre", "midrange"]).is_err());
+        assert!(args(&["1.bmp", "--trim", "0.5"]).is_err());
+        assert!(args(&["1.bmp", "--delta-range", "0:10"]).is_err());
+        assert!(args(&["1.bmp", "--size", "640"]).is_err());
+        assert!(args(&["1.bmp", "--unknown"]).is_err());
+        assert!(args(&["1.bmp", "--format", "xml"]).is_err());
+        assert!(args(&["1.bmp", "--objective", "entropy"]).is_err());
+        assert!(args(&["1.bmp", "2.bmp", "--priors", "1"]).is_err());
+        assert!(args(&["1.bmp", "--search", "annealing"]).is_err());
+        assert!(args(&["1.bmp", "--mode", "best"]).is_err());
+        assert!(args(&["1.bmp", "--parameter", "delta=1:2"]).is_err());
+        assert!(args(&["1.bmp", "--parameter", "radius"]).is_err());
+    }
+
+    #[test]
+    fn priors_and_cost_ratio_are_validated() {
+        assert_eq!(
+            args(&["1.bmp", "2.bmp", "--priors", "0,2", "--cost-ratio", "0.5"])
+                .map(|args| (args.priors, args.cost_ratio)),
+            Ok((vec![0.0, 2.0], 0.5))
+        );
+        assert!(args(&["1.bmp", "2.bmp", "--priors", "-1,3"]).is_err());
+        assert!(args(&["1.bmp", "2.bmp", "--priors", "0,0"]).is_err());
+        assert!(args(&["1.bmp", "2.bmp", "--priors", "NaN,1"]).is_err());
+        assert!(args(&["1.bmp", "2.bmp", "--priors", "inf,1"]).is_err());
+        assert!(args(&["1.bmp", "--cost-ratio", "0"]).is_err());
+        assert!(args(&["1.bmp", "--cost-ratio", "-2"]).is_err());
+        assert!(args(&["1.bmp", "--cost-ratio", "NaN"]).is_err());
+        assert!(args(&["1.bmp", "--cost-ratio", "inf"]).is_err());
+    }
+
+    #[test]
+    fn pipeline_errors_set_exit_codes() {
+        let code = |training: &[&str]| {
+            train(&Args {
+                training: training.iter().map(|path| path.to_string()).collect(),
+                ..Default::default()
+            })
+            .map_err(|err| err.exit_code())
+        };
+
+        assert_eq!(code(&[]), Err(6));
+        assert_eq!(code(&["classes/missing.bmp"]), Err(3));
+        assert_eq!(code(&["Cargo.toml"]), Err(4));
+        assert_eq!(code(&["classes/1.bmp", "classes/1.bmp"]), Err(5));
+        assert_eq!(run(["--unknown".to_owned()]), 2);
+        assert_eq!(
+            args(&["1.bmp", "--delta", "-1"]).map_err(|err| err.exit_code()),
+            Err(2)
+        );
+        assert_eq!(run(["--help".to_owned()]), 0);
+
+        let every_attribute: Vec<String> = (0..fixtures::SHAPE.attributes)
+            .map(|attribute| attribute.to_string())
+            .collect();
+        assert_eq!(
+            run([
+                "classes/1.bmp".to_owned(),
+                "--exclude".to_owned(),
+                every_attribute.join(","),
+            ]),
+            2
+        );
+    }
+
+    #[test]
+    fn pooled_bases_are_parsed() {
+        let base =
+            |value| args(&["1.bmp", "--base", value]).map(|args| (args.base, args.corridor_base));
+
+        assert_eq!(base("all"), Ok((0, CorridorBase::AllClasses)));
+        assert_eq!(base("2,0,2"), Ok((2, CorridorBase::Subset(vec![0, 2]))));
+        assert_eq!(base("1"), Ok((1, CorridorBase::SelectedClass)));
+        assert!(base("1,x").is_err());
+    }
+
+    #[test]
+    fn search_options_are_parsed() {
+        let parsed = args(&[
+            "1.bmp",
+            "--search",
+            "coordinate_descent",
+            "--parameter",
+            "delta=10:50:2",
+            "--parameter",
+            "selection_level",
+        ])
+        .unwrap();
+
+        assert_eq!(
+            parsed.search,
+            Some(Strategy::CoordinateDescent { sweeps: 5 })
+        );
+        assert_eq!(
+            parsed.parameters,
+            [
+                ParameterRange::new(Parameter::Delta, 10.0, 50.0, 2.0),
+                ParameterRange::of(Parameter::SelectionLevel)
+            ]
+        );
+    }
+
+    #[test]
+    fn plots_of_every_window_are_exported() {
+        let dir = std::env::temp_dir().join(format!("tm-plots-{}", std::process::id()));
+        let args = Args {
+            training: ["1.bmp", "2.bmp", "3.bmp"]
+                .iter()
+                .map(|name| format!("classes/{name}"))
+                .collect(),
+            delta: 40.0,
+            plots: Some(dir.clone()),
+            size: (300, 200),
+            ..Default::default()
+        };
+
+        train(&args).unwrap();
+
+        let mut files: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        files.sort();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            files,
+            [
+                "2d_0.png",
+                "2d_0.svg",
+                "2d_1.png",
+                "2d_1.svg",
+                "2d_2.png",
+                "2d_2.svg",
+                "corridor.png",
+                "corridor.svg",
+                "criteria_0.png",
+                "criteria_0.svg",
+                "criteria_1.png",
+                "criteria_1.svg",
+                "criteria_2.png",
+                "criteria_2.svg",
+                "histogram_0.png",
+                "histogram_0.svg",
+                "histogram_1.png",
+                "histogram_1.svg",
+                "histogram_2.png",
+                "histogram_2.svg",
+                "projection.png",
+                "projection.svg",
+            ]
+        );
+    }
+
+    #[test]
+    fn numeric_results_are_exported() {
+        let dir = std::env::temp_dir().join(format!("tm-data-{}", std::process::id()));
+        let args = Args {
+            training: vec!["classes/1.bmp".to_owned(), "classes/2.bmp".to_owned()],
+            exam: vec!["classes/exm.bmp".to_owned()],
+            delta: 40.0,
+            data: Some(dir.clone()),
+            format: DataFormat::Json,
+            ..Default::default()
+        };
+
+        train(&args).unwrap();
+
+        let mut files: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+            .collect();
+        files.sort();
+        let corridor = std::fs::read_to_string(dir.join("corridor.json")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            files,
+            [
+                "attributes.json",
+                "corridor.json",
+                "criteria_0.json",
+                "criteria_1.json",
+                "distances.json",
+                "distances_to_realizations.json",
+                "exam_matrix_0.json",
+                "exam_reference_vectors.json",
+                "histogram_0.json",
+                "histogram_1.json",
+                "matrix_0.json",
+                "matrix_1.json",
+                "priors.json",
+                "projection.json",
+                "quality.json",
+                "reference_vectors.json",
+                "settings.json",
+                "working_space.json",
+            ]
+        );
+        assert_eq!(corridor.lines().count(), 100);
+    }
+}
@@ -1,6 +1,11 @@
 use super::draw::Show;
-use eframe::egui::Ui;
+use crate::error::Error;
+use crate::figure::{Figure, FigureLine, Panel, ToFigure};
+use crate::shape::Shape;
+use crate::table::{Table, ToTables};
+use eframe::egui::{ComboBox, DragValue, Ui};
 use egui_plot::{Legend, Line, Plot, PlotPoints};
+use std::fmt::Display;
 
 #[derive(Default, Debug)]
 pub struct Allowances {
@@ -8,63 +13,380 @@ pub struct Allowances {
     pub upper: Vec<f64>,
 }
 
+/// Statistic of the base class that places the middle of the corridor.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Centre {
+    #[default]
+    Mean,
+    Median,
+    /// Mean of the realizations left after cutting a share from both ends.
+    TrimmedMean,
+    /// The most frequent value, the smallest one of equally frequent values.
+    Mode,
+}
+
+impl Centre {
+    pub const ALL: [Centre; 4] = [
+        Centre::Mean,
+        Centre::Median,
+        Centre::TrimmedMean,
+        Centre::Mode,
+    ];
+}
+
+impl Display for Centre {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Centre::Mean => write!(f, "Mean"),
+            Centre::Median => write!(f, "Median"),
+            Centre::TrimmedMean => write!(f, "Trimmed mean"),
+            Centre::Mode => write!(f, "Mode"),
+        }
+    }
+}
+
+impl std::str::FromStr for Centre {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|centre| centre.to_string().to_lowercase().replace(' ', "_") == value)
+            .ok_or_else(|| Error::InvalidArgument(format!("Unknown centre {value}")))
+    }
+}
+
+/// How delta is turned into the distance from the centre to allowances.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Tolerance {
+    /// Delta in units of attributes.
+    #[default]
+    Absolute,
+    /// Delta in percent of the centre of every attribute.
+    PercentOfExpectation,
+    /// Delta in percent of the standard deviation of every attribute.
+    PercentOfStdDev,
+    /// Delta in percent of the distance from the centre to the lower and upper quantiles,
+    /// so allowances may be asymmetric.
+    PercentOfQuantiles,
+}
+
+impl Tolerance {
+    pub const ALL: [Tolerance; 4] = [
+        Tolerance::Absolute,
+        Tolerance::PercentOfExpectation,
+        Tolerance::PercentOfStdDev,
+        Tolerance::PercentOfQuantiles,
+    ];
+
+    /// Largest delta offered by controls.
+    pub fn max_delta(self) -> f64 {
+        match self {
+            Tolerance::Absolute => u8::MAX as f64,
+            Tolerance::PercentOfExpectation => 100.0,
+            Tolerance::PercentOfStdDev | Tolerance::PercentOfQuantiles => 300.0,
+        }
+    }
+}
+
+impl Display for Tolerance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Tolerance::Absolute => write!(f, "Absolute"),
+            Tolerance::PercentOfExpectation => write!(f, "Percent of expectation"),
+            Tolerance::PercentOfStdDev => write!(f, "Percent of std dev"),
+            Tolerance::PercentOfQuantiles => write!(f, "Percent of quantiles"),
+        }
+    }
+}
+
+impl std::str::FromStr for Tolerance {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|tolerance| tolerance.to_string().to_lowercase().replace(' ', "_") == value)
+            .ok_or_else(|| Error::InvalidArgument(format!("Unknown tolerance {value}")))
+    }
+}
+
+/// Statistics of the base class the corridor is built from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Statistics {
+    pub centre: Centre,
+    /// Share of realizations cut from each end by [`Centre::TrimmedMean`].
+    pub trim: f64,
+    pub tolerance: Tolerance,
+    /// Level of the lower quantile of [`Tolerance::PercentOfQuantiles`],
+    /// the upper quantile is at `1 - quantile`.
+    pub quantile: f64,
+}
+
+impl Default for Statistics {
+    fn default() -> Self {
+        Self {
+            centre: Centre::Mean,
+            trim: 0.1,
+            tolerance: Tolerance::Absolute,
+            quantile: 0.1,
+        }
+    }
+}
+
+impl Statistics {
+    /// Shows the controls and returns `true` if any statistic has changed.
+    pub fn show(&mut self, ui: &mut Ui) -> bool {
+        let mut changed = false;
+
+        ui.horizontal(|ui| {
+            ui.label("Centre");
+            changed |= combo_box("Centre", &mut self.centre, &Centre::ALL, ui);
+
+            if self.centre == Centre::TrimmedMean {
+                ui.label("Trim");
+                changed |= ui
+                    .add(
+                        DragValue::new(&mut self.trim)
+                            .speed(0.01)
+                            .clamp_range(0.0..=0.49),
+                    )
+                    .changed();
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Delta in");
+            changed |= combo_box("Tolerance", &mut self.tolerance, &Tolerance::ALL, ui);
+
+            if self.tolerance == Tolerance::PercentOfQuantiles {
+                ui.label("Quantile");
+                changed |= ui
+                    .add(
+                        DragValue::new(&mut self.quantile)
+                            .speed(0.01)
+                            .clamp_range(0.0..=0.49),
+                    )
+                    .changed();
+            }
+        });
+
+        changed
+    }
+}
+
+fn combo_box<T: Copy + PartialEq + Display>(
+    label: &str,
+    value: &mut T,
+    options: &[T],
+    ui: &mut Ui,
+) -> bool {
+    let mut changed = false;
+
+    ComboBox::from_id_source(label)
+        .selected_text(value.to_string())
+        .show_ui(ui, |ui| {
+            options.iter().for_each(|&option| {
+                changed |= ui
+                    .selectable_value(value, option, option.to_string())
+                    .changed();
+            });
+        });
+
+    changed
+}
+
 #[derive(Debug, Default)]
 pub struct Corridor {
+    /// Sorted values of every attribute over all realizations.
+    values: Vec<Vec<f64>>,
+    /// Centre of every attribute.
     expectation: Vec<f64>,
     pub allowances: Allowances,
-    delta: u8,
+    delta: f64,
+    /// Deltas of attributes that override the common delta.
+    attribute_deltas: Vec<Option<f64>>,
+    statistics: Statistics,
 }
 
 impl Corridor {
-    pub fn new(selected_class: &[u8], size: (usize, usize)) -> Self {
-        let mut created = Corridor {
-            expectation: Corridor::math_expectation(selected_class, size),
-            ..Default::default()
-        };
+    pub fn new(selected_class: &[u8], shape: Shape) -> Self {
+        let mut created = Corridor::default();
 
-        created.calculate_allowances();
+        created.set_base_class(selected_class, shape);
 
         created
     }
 
-    pub fn set_base_class(&mut self, selected_class: &[u8], size: (usize, usize)) {
-        self.expectation = Corridor::math_expectation(selected_class, size);
-        self.calculate_allowances();
+    /// Builds the corridor around the class.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `selected_class` does not match `shape`.
+    pub fn set_base_class(&mut self, selected_class: &[u8], shape: Shape) {
+        self.values = Corridor::attribute_values(selected_class, shape);
+        self.calculate_centre();
     }
 
-    pub fn delta(&mut self, delta: u8) {
+    pub fn delta(&mut self, delta: f64) {
         self.delta = delta;
         self.calculate_allowances();
     }
 
-    fn calculate_allowances(&mut self) {
-        self.calculate_lower_allowance();
-        self.calculate_upper_allowance();
+    pub fn statistics(&mut self, statistics: Statistics) {
+        if self.statistics != statistics {
+            self.statistics = statistics;
+            self.calculate_centre();
+        }
+    }
+
+    /// Sets the delta of one attribute, `None` restores the common delta.
+    pub fn attribute_delta(&mut self, attribute: usize, delta: Option<f64>) {
+        if self.attribute_deltas.len() <= attribute {
+            self.attribute_deltas.resize(attribute + 1, None);
+        }
+
+        self.attribute_deltas[attribute] = delta;
+        self.calculate_allowances();
     }
 
-    fn math_expectation(selected_class: &[u8], (w, h): (usize, usize)) -> Vec<f64> {
-        (0..w).map(|i| {
-            (0..h).map(|j| {
-                selected_class[i + j * h] as u32
-            }).sum::<u32>() as f64 / h as f64
-        }).collect()
+    /// Returns deltas set for single attributes, `None` where the common delta is used.
+    pub fn attribute_deltas(&self) -> &[Option<f64>] {
+        &self.attribute_deltas
     }
 
-    fn calculate_lower_allowance(&mut self) {
-        self.allowances.lower = self
-            .expectation
-            .iter()
-            .map(|x| x - self.delta as f64)
-            .collect();
+    pub fn has_attribute_deltas(&self) -> bool {
+        self.attribute_deltas.iter().any(Option::is_some)
     }
 
-    fn calculate_upper_allowance(&mut self) {
-        self.allowances.upper = self
-            .expectation
+    /// Restores the common delta of every attribute.
+    pub fn clear_attribute_deltas(&mut self) {
+        self.attribute_deltas.clear();
+        self.calculate_allowances();
+    }
+
+    /// Returns the delta of the attribute.
+    pub fn delta_of(&self, attribute: usize) -> f64 {
+        self.attribute_deltas
+            .get(attribute)
+            .copied()
+            .flatten()
+            .unwrap_or(self.delta)
+    }
+
+    /// Returns distances from the centre of the attribute to its lower and upper allowances.
+    fn half_widths(&self, attribute: usize) -> (f64, f64) {
+        let values = &self.values[attribute];
+        let centre = self.expectation[attribute];
+        let (lower, upper) = match self.statistics.tolerance {
+            Tolerance::Absolute => (1.0, 1.0),
+            Tolerance::PercentOfExpectation => (centre.abs() / 100.0, centre.abs() / 100.0),
+            Tolerance::PercentOfStdDev => {
+                let std_dev = standard_deviation(values) / 100.0;
+                (std_dev, std_dev)
+            }
+            Tolerance::PercentOfQuantiles => (
+                (centre - quantile(values, self.statistics.quantile)).max(0.0) / 100.0,
+                (quantile(values, 1.0 - self.statistics.quantile) - centre).max(0.0) / 100.0,
+            ),
+        };
+        let delta = self.delta_of(attribute);
+
+        (delta * lower, delta * upper)
+    }
+
+    /// Returns the name of the centre on plots.
+    fn centre_name(&self) -> String {
+        match self.statistics.centre {
+            Centre::Mean => "Expectation".to_owned(),
+            centre => centre.to_string(),
+        }
+    }
+
+    fn calculate_centre(&mut self) {
+        self.expectation = self
+            .values
             .iter()
-            .map(|x| x + self.delta as f64)
+            .map(|values| match self.statistics.centre {
+                Centre::Mean => mean(values),
+                Centre::Median => quantile(values, 0.5),
+                Centre::TrimmedMean => {
+                    let cut = (values.len() as f64 * self.statistics.trim) as usize;
+                    mean(&values[cut..values.len() - cut])
+                }
+                Centre::Mode => mode(values),
+            })
             .collect();
+
+        self.calculate_allowances();
+    }
+
+    fn calculate_allowances(&mut self) {
+        let (lower, upper) = (0..self.expectation.len())
+            .map(|i| {
+                let (lower, upper) = self.half_widths(i);
+                (self.expectation[i] - lower, self.expectation[i] + upper)
+            })
+            .unzip();
+
+        self.allowances = Allowances { lower, upper };
     }
+
+    /// Returns sorted values of every attribute over all realizations.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `selected_class` does not match `shape`.
+    fn attribute_values(selected_class: &[u8], shape: Shape) -> Vec<Vec<f64>> {
+        shape.validate(selected_class);
+
+        (0..shape.attributes)
+            .map(|i| {
+                let mut values: Vec<f64> = (0..shape.realizations)
+                    .map(|j| selected_class[i + j * shape.attributes] as f64)
+                    .collect();
+                values.sort_by(f64::total_cmp);
+                values
+            })
+            .collect()
+    }
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+fn standard_deviation(values: &[f64]) -> f64 {
+    let mean = mean(values);
+
+    (values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / values.len() as f64).sqrt()
+}
+
+/// Quantile of sorted values interpolated between the closest ones.
+fn quantile(sorted: &[f64], level: f64) -> f64 {
+    if sorted.is_empty() {
+        return f64::NAN;
+    }
+
+    let position = level.clamp(0.0, 1.0) * (sorted.len() - 1) as f64;
+    let below = sorted[position.floor() as usize];
+    let above = sorted[position.ceil() as usize];
+
+    below + (above - below) * position.fract()
+}
+
+/// The most frequent of sorted values, the smallest one if several are equally frequent.
+fn mode(sorted: &[f64]) -> f64 {
+    sorted
+        .chunk_by(|a, b| a == b)
+        .fold((f64::NAN, 0), |(mode, count), run| {
+            if run.len() > count {
+                (run[0], run.len())
+            } else {
+                (mode, count)
+            }
+        })
+        .0
 }
 
 impl Show for Corridor {
@@ -74,13 +396,173 @@ impl Show for Corridor {
             .auto_bounds_x()
             .auto_bounds_y()
             .show(ui, |ui| {
-                ui.line(Line::new(PlotPoints::from_ys_f64(&self.expectation)).name("Expectation"));
                 ui.line(
-                    Line::new(PlotPoints::from_ys_f64(&self.allowances.lower)).name("Lower allowance"),
+                    Line::new(PlotPoints::from_ys_f64(&self.expectation)).name(self.centre_name()),
+                );
+                ui.line(
+                    Line::new(PlotPoints::from_ys_f64(&self.allowances.lower))
+                        .name("Lower allowance"),
                 );
                 ui.line(
-                    Line::new(PlotPoints::from_ys_f64(&self.allowances.upper)).name("Upper allowance"),
+                    Line::new(PlotPoints::from_ys_f64(&self.allowances.upper))
+                        .name("Upper allowance"),
                 );
             });
     }
 }
+
+impl ToFigure for Corridor {
+    fn figure(&self) -> Figure {
+        Figure::new(vec![Panel::new("Corridor")
+            .line(FigureLine::from_ys(self.centre_name(), &self.expectation))
+            .line(FigureLine::from_ys(
+                "Lower allowance",
+                &self.allowances.lower,
+            ))
+            .line(FigureLine::from_ys(
+                "Upper allowance",
+                &self.allowances.upper,
+            ))])
+    }
+}
+
+impl ToTables for Corridor {
+    fn tables(&self) -> Vec<Table> {
+        let mut table = Table::new("corridor", &["attribute", "expectation", "lower", "upper"]);
+
+        (0..self.expectation.len()).for_each(|i| {
+            table.push(vec![
+                i.into(),
+                self.expectation[i].into(),
+                self.allowances.lower[i].into(),
+                self.allowances.upper[i].into(),
+            ]);
+        });
+
+        vec![table]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expectation_of_rectangular_class_is_calculated_per_attribute() {
+        // 3 attributes x 2 realizations
+        let class = [0, 10, 20, 100, 110, 120];
+
+        let corridor = Corridor::new(&class, Shape::new(3, 2));
+
+        assert_eq!(corridor.expectation, vec![50.0, 60.0, 70.0]);
+    }
+
+    #[test]
+    fn expectation_of_tall_class_is_calculated_per_attribute() {
+        // 2 attributes x 3 realizations
+        let class = [0, 30, 10, 60, 20, 90];
+
+        let corridor = Corridor::new(&class, Shape::new(2, 3));
+
+        assert_eq!(corridor.expectation, vec![10.0, 60.0]);
+    }
+
+    #[test]
+    fn allowances_follow_delta() {
+        let mut corridor = Corridor::new(&[10, 20, 30, 40], Shape::new(2, 2));
+
+        corridor.delta(5.0);
+
+        assert_eq!(corridor.allowances.lower, vec![15.0, 25.0]);
+        assert_eq!(corridor.allowances.upper, vec![25.0, 35.0]);
+    }
+
+    #[test]
+    fn relative_tolerance_scales_delta_per_attribute() {
+        // 2 attributes x 2 realizations, expectation [20, 30], std dev [10, 10]
+        let mut corridor = Corridor::new(&[10, 20, 30, 40], Shape::new(2, 2));
+
+        corridor.delta(50.0);
+        corridor.statistics(Statistics {
+            tolerance: Tolerance::PercentOfExpectation,
+            ..Default::default()
+        });
+
+        assert_eq!(corridor.allowances.lower, vec![10.0, 15.0]);
+        assert_eq!(corridor.allowances.upper, vec![30.0, 45.0]);
+
+        corridor.statistics(Statistics {
+            tolerance: Tolerance::PercentOfStdDev,
+            ..Default::default()
+        });
+
+        assert_eq!(corridor.allowances.lower, vec![15.0, 25.0]);
+        assert_eq!(corridor.allowances.upper, vec![25.0, 35.0]);
+    }
+
+    #[test]
+    fn robust_centres_ignore_outlier() {
+        // 1 attribute x 5 realizations with an outlier
+        let class = [10, 10, 20, 30, 255];
+        let mut corridor = Corridor::new(&class, Shape::new(1, 5));
+
+        let mut centre = |centre| {
+            corridor.statistics(Statistics {
+                centre,
+                trim: 0.2,
+                ..Default::default()
+            });
+            corridor.expectation[0]
+        };
+
+        assert_eq!(centre(Centre::Mean), 65.0);
+        assert_eq!(centre(Centre::Median), 20.0);
+        assert_eq!(centre(Centre::TrimmedMean), 20.0);
+        assert_eq!(centre(Centre::Mode), 10.0);
+    }
+
+    #[test]
+    fn quantile_tolerance_gives_asymmetric_allowances() {
+        // 1 attribute x 5 realizations, median 20, quantiles 0.25 and 0.75 at 10 and 40
+        let mut corridor = Corridor::new(&[0, 10, 20, 40, 100], Shape::new(1, 5));
+
+        corridor.delta(50.0);
+        corridor.statistics(Statistics {
+            centre: Centre::Median,
+            tolerance: Tolerance::PercentOfQuantiles,
+            quantile: 0.25,
+            ..Default::default()
+        });
+
+        assert_eq!(corridor.allowances.lower, vec![15.0]);
+        assert_eq!(corridor.allowances.upper, vec![30.0]);
+    }
+
+    #[test]
+    fn tolerance_is_parsed_from_snake_case() {
+        assert_eq!("percent_of_std_dev".parse(), Ok(Tolerance::PercentOfStdDev));
+        assert!("relative".parse::<Tolerance>().is_err());
+        assert_eq!("trimmed_mean".parse(), Ok(Centre::TrimmedMean));
+    }
+
+    #[test]
+    fn attribute_delta_overrides_common_delta() {
+        let mut corridor = Corridor::new(&[10, 20, 30, 40], Shape::new(2, 2));
+
+        corridor.delta(5.0);
+        corridor.attribute_delta(1, Some(10.5));
+
+        assert_eq!(corridor.allowances.lower, vec![15.0, 19.5]);
+        assert_eq!(corridor.allowances.upper, vec![25.0, 40.5]);
+
+        corridor.clear_attribute_deltas();
+
+        assert_eq!(corridor.allowances.lower, vec![15.0, 25.0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn class_not_matching_shape_panics() {
+        Corridor::new(&[0; 6], Shape::new(4, 2));
+    }
+}
@@ -1,19 +1,25 @@
+use crate::criterion::CriterionKind;
 use crate::draw::Show;
+use crate::figure::{Figure, FigureLine, Panel, ToFigure};
+use crate::table::{Table, ToTables};
+use crate::working_space::{Region, WorkingSpace};
 
-use eframe::egui::Ui;
-use egui_plot::{Legend, Line, Plot, PlotPoints};
+use eframe::egui::{Grid, Ui};
+use egui_plot::{Legend, Line, Plot, PlotPoints, Polygon};
 
 #[derive(Debug, Default)]
 pub struct Criteria {
     pub characteristics: Vec<Characteristics>,
-    pub kullback_criteria: Vec<f64>,
-    pub shannon_criteria: Vec<f64>,
+    /// Values of every criterion in the order of [`CriterionKind::ALL`].
+    criteria: Vec<Vec<f64>>,
     working_space: Vec<usize>,
-    pub r_kullback: Vec<f64>,
-    pub r_shannon: Vec<f64>,
+    /// Radii excluded from working space by each of its constraints.
+    regions: Vec<Region>,
+    /// Optimal radii by every criterion in the order of [`CriterionKind::ALL`].
+    radii: Vec<Vec<f64>>,
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct Characteristics {
     pub d1: f64,
     pub beta: f64,
@@ -21,13 +27,53 @@ pub struct Characteristics {
     pub d2: f64,
 }
 
+impl Characteristics {
+    /// Scales errors by their relative costs, keeping their average cost unchanged.
+    ///
+    /// `cost_ratio` is the cost of an error of the first kind (alpha)
+    /// relative to an error of the second kind (beta).
+    pub fn weighted(&self, cost_ratio: f64) -> Self {
+        let alpha = (self.alpha * 2.0 * cost_ratio / (1.0 + cost_ratio)).min(1.0);
+        let beta = (self.beta * 2.0 / (1.0 + cost_ratio)).min(1.0);
+
+        Self {
+            d1: 1.0 - alpha,
+            beta,
+            alpha,
+            d2: 1.0 - beta,
+        }
+    }
+}
+
+/// Prior probabilities of classes and the relative cost of errors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Weights {
+    /// Prior probability of every class, summing to one.
+    pub priors: Vec<f64>,
+    /// Cost of an error of the first kind relative to an error of the second kind.
+    pub cost_ratio: f64,
+}
+
 impl Criteria {
+    /// Calculates criteria of the class `self_index`.
+    ///
+    /// Classes may have different numbers of realizations: d1 is the share of realizations
+    /// of the class, and realizations of other classes are scaled to its number.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any class has no realizations or there is not a prior for every class.
     pub fn new(
         self_index: usize,
         distances: &[Vec<u32>],
-        number_of_realizations: usize,
         distance: u32,
+        weights: &Weights,
+        working_space: &WorkingSpace,
     ) -> Self {
+        distances.iter().for_each(|d| assert!(!d.is_empty()));
+        assert_eq!(weights.priors.len(), distances.len());
+        let number_of_realizations = distances[self_index].len();
+
         let max_radius = Self::calculate_max_radius(distances);
 
         let self_realizations: Vec<usize> = (0..max_radius)
@@ -38,133 +84,132 @@ impl Criteria {
                     .count()
             })
             .collect();
+        let others_weights = Self::calculate_others_weights(distances, self_index, &weights.priors);
         let others_realizations =
-            Self::calculate_number_of_others_realizations(distances, self_index, max_radius);
+            Self::calculate_number_of_others_realizations(distances, max_radius, &others_weights);
+        let number_of_others_realizations = distances
+            .iter()
+            .zip(&others_weights)
+            .map(|(distances, weight)| distances.len() as f64 * weight)
+            .sum();
 
         let characteristics = Self::calculate_characteristics(
             &self_realizations,
             &others_realizations,
             number_of_realizations,
-            number_of_realizations * distances.len() - 1,
+            number_of_others_realizations,
             max_radius as usize,
         );
 
-        let kullback_criteria = Self::kullback_criteria(&characteristics);
-        let shannon_criteria = Self::shannon_criteria(&characteristics);
+        let weighted: Vec<Characteristics> = characteristics
+            .iter()
+            .map(|c| c.weighted(weights.cost_ratio))
+            .collect();
 
-        let working_space: Vec<usize> = characteristics
+        let prior = weights.priors[self_index];
+        let criteria: Vec<Vec<f64>> = CriterionKind::ALL
             .iter()
-            .enumerate()
-            .skip(1)
-            .filter(|(i, c)| {
-                c.d1 >= 0.5 && c.d1 <= 1.0 && c.d2 >= 0.5 && c.d2 <= 1.0 && *i < distance as usize
+            .map(|kind| {
+                weighted
+                    .iter()
+                    .map(|c| kind.criterion().value(c, prior))
+                    .collect()
             })
-            .map(|(i, _)| i)
             .collect();
 
-        let r_kullback = Self::find_radius(&kullback_criteria, &working_space);
-        let r_shannon = Self::find_radius(&shannon_criteria, &working_space);
+        let regions = working_space.regions(&weighted, distance);
+        let working_space = working_space.select(&weighted, distance);
+
+        let radii = CriterionKind::ALL
+            .iter()
+            .zip(&criteria)
+            .map(|(kind, criteria)| Self::find_radius(*kind, criteria, &working_space))
+            .collect();
 
         Self {
             characteristics,
-            kullback_criteria,
-            shannon_criteria,
+            criteria,
             working_space,
-            r_kullback,
-            r_shannon,
+            regions,
+            radii,
         }
     }
 
-    pub fn max_shannon_criteria(&self) -> Option<(usize, f64)> {
-        self.working_space
-            .iter()
-            .map(|&i| (i, self.shannon_criteria[i]))
-            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+    /// Returns values of the criterion for every radius.
+    pub fn values(&self, kind: CriterionKind) -> &[f64] {
+        &self.criteria[kind.index()]
+    }
+
+    /// Returns radii with the best value of the criterion within working space.
+    pub fn radii(&self, kind: CriterionKind) -> &[f64] {
+        &self.radii[kind.index()]
     }
 
-    pub fn max_kullback_criteria(&self) -> Option<(usize, f64)> {
+    /// Returns the radius with the best finite value of the criterion within working space.
+    pub fn best_criteria(&self, kind: CriterionKind) -> Option<(usize, f64)> {
+        let criteria = self.values(kind);
+
         self.working_space
             .iter()
-            .map(|&i| (i, self.kullback_criteria[i]))
-            .filter(|(_, criteria)| criteria.is_normal())
-            .max_by(|(_, a), (_, b)| a.partial_cmp(b).unwrap())
+            .map(|&i| (i, criteria[i]))
+            .filter(|(_, criteria)| criteria.is_finite())
+            .max_by(|(_, a), (_, b)| Self::score(kind, *a).total_cmp(&Self::score(kind, *b)))
     }
 
-    pub fn min_radius(&self) -> f64 {
-        self.r_kullback
+    /// Whether any radius satisfies the working space, exam cannot recognize a class without one.
+    pub fn has_working_space(&self) -> bool {
+        !self.working_space.is_empty()
+    }
+
+    /// Returns the smallest optimal radius by the criterion, `None` without working space.
+    pub fn min_radius(&self, kind: CriterionKind) -> Option<f64> {
+        self.radii(kind).iter().copied().min_by(f64::total_cmp)
+    }
+
+    /// Shaded intervals of regions, covering the whole step of every excluded radius.
+    fn region_intervals(region: &Region) -> Vec<[f64; 2]> {
+        region
+            .intervals
             .iter()
-            .min_by(|a, b| a.partial_cmp(b).unwrap())
-            .unwrap_or(&0.0)
-            .to_owned()
+            .map(|interval| [interval.start as f64 - 0.5, interval.end as f64 - 0.5])
+            .collect()
+    }
+
+    /// Value of the criterion that is higher for better radii.
+    fn score(kind: CriterionKind, value: f64) -> f64 {
+        if kind.criterion().lower_is_better() {
+            -value
+        } else {
+            value
+        }
     }
 
-    fn find_radius(criteria: &[f64], working_space: &[usize]) -> Vec<f64> {
+    fn find_radius(kind: CriterionKind, criteria: &[f64], working_space: &[usize]) -> Vec<f64> {
         let max = working_space
             .iter()
-            .map(|&i| criteria[i])
+            .map(|&i| Self::score(kind, criteria[i]))
             .reduce(f64::max)
             .unwrap_or_default();
 
         working_space
             .iter()
-            .filter(|&&i| criteria[i] == max)
+            .filter(|&&i| Self::score(kind, criteria[i]) == max)
             .map(|i| *i as f64)
             .collect()
     }
 
-    fn kullback_criteria(characteristics: &[Characteristics]) -> Vec<f64> {
-        characteristics
-            .iter()
-            .map(|c| {
-                ((2.0 - (c.alpha + c.beta)) / (c.alpha + c.beta)).log2()
-                    * (1.0 - (c.alpha + c.beta))
-            })
-            .collect()
-    }
-
-    fn shannon_criteria(characteristics: &[Characteristics]) -> Vec<f64> {
-        characteristics
-            .iter()
-            .map(|c| {
-                let a = c.alpha;
-                let b = c.beta;
-                let d1 = c.d1;
-                let d2 = c.d2;
-
-                let divisor1 = a + d2;
-                let divisor2 = d1 + b;
-
-                1.0 + 0.5
-                    * (Self::divide_and_multiply_log2(a, divisor1)
-                        + Self::divide_and_multiply_log2(d1, divisor2)
-                        + Self::divide_and_multiply_log2(b, divisor2)
-                        + Self::divide_and_multiply_log2(d2, divisor1))
-            })
-            .collect()
-    }
-
-    fn divide_and_multiply_log2(x: f64, y: f64) -> f64 {
-        let result = (x / y) * (x / y).log2();
-
-        if result.is_normal() {
-            result
-        } else {
-            0.0
-        }
-    }
-
     fn calculate_characteristics(
         realizations: &[usize],
-        others_realizations: &[usize],
+        others_realizations: &[f64],
         number_of_realizations: usize,
-        number_of_others_realizations: usize,
+        number_of_others_realizations: f64,
         max_radius: usize,
     ) -> Vec<Characteristics> {
         (0..max_radius)
             .map(|i| {
                 let d1 = realizations[i] as f64 / number_of_realizations as f64;
                 let alpha = 1.0 - d1;
-                let beta = others_realizations[i] as f64 / number_of_others_realizations as f64;
+                let beta = others_realizations[i] / number_of_others_realizations;
                 let d2 = 1.0 - beta;
 
                 Characteristics {
@@ -185,22 +230,50 @@ impl Criteria {
             .unwrap_or(&0)
     }
 
-    fn calculate_number_of_others_realizations(
+    /// Weights realizations of other classes, scaled to the number of realizations of the class
+    /// and by their prior relative to the average prior of other classes. The class itself
+    /// has no weight.
+    fn calculate_others_weights(
         distances_of_classes: &[Vec<u32>],
         self_index: usize,
+        priors: &[f64],
+    ) -> Vec<f64> {
+        let others_prior = 1.0 - priors[self_index];
+        let others = distances_of_classes.len() - 1;
+        let realizations = distances_of_classes[self_index].len() as f64;
+
+        (0..distances_of_classes.len())
+            .map(|class| {
+                let scale = realizations / distances_of_classes[class].len() as f64;
+
+                if class == self_index {
+                    0.0
+                } else if others_prior > 0.0 {
+                    priors[class] * others as f64 / others_prior * scale
+                } else {
+                    scale
+                }
+            })
+            .collect()
+    }
+
+    /// Counts weighted realizations of other classes within every radius.
+    fn calculate_number_of_others_realizations(
+        distances_of_classes: &[Vec<u32>],
         max_radius: u32,
-    ) -> Vec<usize> {
+        others_weights: &[f64],
+    ) -> Vec<f64> {
         (0..max_radius)
             .map(|radius| {
                 distances_of_classes
                     .iter()
-                    .enumerate()
-                    .filter(|(i, _)| *i != self_index)
-                    .map(|(_, distances_to_realizations)| {
+                    .zip(others_weights)
+                    .map(|(distances_to_realizations, weight)| {
                         distances_to_realizations
                             .iter()
                             .filter(|&distance| distance <= &radius)
-                            .count()
+                            .count() as f64
+                            * weight
                     })
                     .sum()
             })
@@ -210,49 +283,223 @@ impl Criteria {
 
 impl Show for Criteria {
     fn show(&self, ui: &mut Ui) {
-        let available_width = ui.max_rect().width() - 10.0;
-        let available_height = ui.max_rect().height() - 10.0;
-
-        ui.horizontal(|ui| {
-            ui.set_height(available_height);
-
-            Plot::new("Kullback")
-                .legend(Legend::default())
-                .width(available_width / 2.0)
-                .auto_bounds_x()
-                .auto_bounds_y()
-                .show(ui, |ui| {
-                    ui.line(
-                        Line::new(PlotPoints::from_ys_f64(&self.kullback_criteria))
-                            .name("Kullback"),
-                    );
-
-                    let points: PlotPoints = self
-                        .working_space
-                        .iter()
-                        .map(|&i| [i as f64, self.kullback_criteria[i]])
-                        .collect();
-                    ui.line(Line::new(points).name("Working space").fill(0.0));
-                });
+        let columns = 4;
+        let rows = CriterionKind::ALL.len().div_ceil(columns);
+        let spacing = ui.spacing().item_spacing;
+        let width = ui.available_width() / columns as f32 - spacing.x;
+        let height = ui.available_height() / rows as f32 - spacing.y;
+
+        if !self.has_working_space() {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                "No radius satisfies the working space",
+            );
+        }
 
-            Plot::new("Shannon")
-                .legend(Legend::default())
-                .width(available_width / 2.0)
-                .auto_bounds_x()
-                .auto_bounds_y()
-                .show(ui, |ui| {
-                    ui.line(
-                        Line::new(PlotPoints::from_ys_f64(&self.shannon_criteria)).name("Shannon"),
-                    );
-
-                    let points: PlotPoints = self
-                        .working_space
-                        .iter()
-                        .map(|&i| [i as f64, self.shannon_criteria[i]])
-                        .collect();
-
-                    ui.line(Line::new(points).name("Working space").fill(0.0));
+        Grid::new("Criteria plots").show(ui, |ui| {
+            CriterionKind::ALL
+                .iter()
+                .enumerate()
+                .for_each(|(i, &kind)| {
+                    let criteria = self.values(kind);
+
+                    Plot::new(kind.to_string())
+                        .legend(Legend::default())
+                        .width(width)
+                        .height(height)
+                        .auto_bounds_x()
+                        .auto_bounds_y()
+                        .show(ui, |ui| {
+                            let (min, max) = criteria
+                                .iter()
+                                .filter(|c| c.is_finite())
+                                .fold((0.0f64, 0.0f64), |(min, max), &c| (min.min(c), max.max(c)));
+
+                            self.regions.iter().for_each(|region| {
+                                Self::region_intervals(region).into_iter().for_each(
+                                    |[start, end]| {
+                                        ui.polygon(
+                                            Polygon::new(vec![
+                                                [start, min],
+                                                [end, min],
+                                                [end, max],
+                                                [start, max],
+                                            ])
+                                            .name(&region.name)
+                                            .fill_color(region.color.gamma_multiply(0.15))
+                                            .stroke((0.0, region.color)),
+                                        );
+                                    },
+                                );
+                            });
+
+                            ui.line(
+                                Line::new(PlotPoints::from_ys_f64(criteria)).name(kind.to_string()),
+                            );
+
+                            let points: PlotPoints = self
+                                .working_space
+                                .iter()
+                                .map(|&i| [i as f64, criteria[i]])
+                                .collect();
+
+                            ui.line(Line::new(points).name("Working space").fill(0.0));
+                        });
+
+                    if (i + 1) % columns == 0 {
+                        ui.end_row();
+                    }
                 });
         });
     }
 }
+
+impl ToFigure for Criteria {
+    fn figure(&self) -> Figure {
+        let panels = CriterionKind::ALL
+            .iter()
+            .map(|&kind| {
+                let criteria = self.values(kind);
+                let working_space = self
+                    .working_space
+                    .iter()
+                    .map(|&i| [i as f64, criteria[i]])
+                    .collect();
+
+                let panel = Panel::new(kind.to_string())
+                    .line(FigureLine::from_ys(kind.to_string(), criteria))
+                    .line(FigureLine::new("Working space", working_space).fill(0.0));
+
+                self.regions.iter().fold(panel, |panel, region| {
+                    panel.region(&region.name, region.color, Self::region_intervals(region))
+                })
+            })
+            .collect();
+
+        Figure::new(panels).columns(4)
+    }
+}
+
+impl ToTables for Criteria {
+    fn tables(&self) -> Vec<Table> {
+        let criteria: Vec<String> = CriterionKind::ALL
+            .iter()
+            .map(|kind| kind.column())
+            .collect();
+        let mut columns = vec!["radius", "d1", "alpha", "beta", "d2"];
+        columns.extend(criteria.iter().map(String::as_str));
+        columns.push("working_space");
+
+        let mut table = Table::new("criteria", &columns);
+
+        self.characteristics
+            .iter()
+            .enumerate()
+            .for_each(|(radius, c)| {
+                let mut row = vec![
+                    radius.into(),
+                    c.d1.into(),
+                    c.alpha.into(),
+                    c.beta.into(),
+                    c.d2.into(),
+                ];
+                row.extend(self.criteria.iter().map(|criteria| criteria[radius].into()));
+                row.push(self.working_space.contains(&radius).into());
+
+                table.push(row);
+            });
+
+        vec![table]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn criteria(priors: Vec<f64>, cost_ratio: f64) -> Criteria {
+        let distances = [vec![0, 5], vec![1, 1], vec![9, 9]];
+
+        Criteria::new(
+            0,
+            &distances,
+            10,
+            &Weights { priors, cost_ratio },
+            &WorkingSpace::default(),
+        )
+    }
+
+    #[test]
+    fn equal_costs_keep_characteristics() {
+        let c = Characteristics {
+            d1: 0.75,
+            beta: 0.5,
+            alpha: 0.25,
+            d2: 0.5,
+        };
+
+        assert_eq!(c.weighted(1.0), c);
+        assert_eq!(c.weighted(3.0).alpha, 0.375);
+        assert_eq!(c.weighted(3.0).beta, 0.25);
+    }
+
+    #[test]
+    fn class_without_working_space_has_no_radius() {
+        let criteria = Criteria::new(
+            0,
+            &[vec![0, 5], vec![1, 1], vec![9, 9]],
+            10,
+            &Weights {
+                priors: vec![1.0 / 3.0; 3],
+                cost_ratio: 1.0,
+            },
+            &WorkingSpace {
+                min_d1: 1.0,
+                min_d2: 1.0,
+                ..Default::default()
+            },
+        );
+
+        assert!(!criteria.has_working_space());
+        assert_eq!(criteria.min_radius(CriterionKind::Kullback), None);
+        assert_eq!(criteria.best_criteria(CriterionKind::Shannon), None);
+        assert!(criteria
+            .values(CriterionKind::Kullback)
+            .iter()
+            .all(|value| value.is_finite()));
+    }
+
+    #[test]
+    fn other_classes_are_weighted_by_priors() {
+        let equal = criteria(vec![1.0 / 3.0; 3], 1.0);
+        let weighted = criteria(vec![0.2, 0.6, 0.2], 1.0);
+
+        assert!((equal.characteristics[1].beta - 0.5).abs() < 1e-12);
+        assert!((weighted.characteristics[1].beta - 0.75).abs() < 1e-12);
+    }
+
+    #[test]
+    fn other_classes_are_scaled_to_number_of_realizations() {
+        let criteria = Criteria::new(
+            0,
+            &[vec![0, 5], vec![1], vec![9, 9, 9, 9]],
+            10,
+            &Weights {
+                priors: vec![1.0 / 3.0; 3],
+                cost_ratio: 1.0,
+            },
+            &WorkingSpace::default(),
+        );
+
+        assert_eq!(criteria.characteristics[5].d1, 1.0);
+        assert!((criteria.characteristics[1].beta - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn costly_false_alarms_shrink_working_space() {
+        assert!(criteria(vec![1.0 / 3.0; 3], 1.0).working_space.contains(&1));
+        assert!(!criteria(vec![1.0 / 3.0; 3], 0.25)
+            .working_space
+            .contains(&1));
+    }
+}
@@ -0,0 +1,347 @@
+use std::fmt::Display;
+
+use crate::{criteria::Characteristics, error::Error};
+
+/// Information criterion of a container calculated from its characteristics.
+pub trait Criterion {
+    fn name(&self) -> &'static str;
+
+    /// Returns the value of the criterion for a class with the prior probability `prior`.
+    fn value(&self, characteristics: &Characteristics, prior: f64) -> f64;
+
+    /// Whether the best radius has the lowest value instead of the highest.
+    fn lower_is_better(&self) -> bool {
+        false
+    }
+}
+
+/// Value of `x * log2(x / y)`, defined as zero where the logarithm is not.
+fn x_log2(x: f64, y: f64) -> f64 {
+    let result = x * (x / y).log2();
+
+    if result.is_normal() {
+        result
+    } else {
+        0.0
+    }
+}
+
+/// Kullback criterion, finite where the sum of errors is 0 or 2.
+pub struct Kullback;
+
+impl Kullback {
+    /// Smallest sum of errors and its distance to 2, far below one error of real classes.
+    const EPSILON: f64 = 1e-6;
+}
+
+impl Criterion for Kullback {
+    fn name(&self) -> &'static str {
+        "Kullback"
+    }
+
+    fn value(&self, c: &Characteristics, _: f64) -> f64 {
+        let errors = (c.alpha + c.beta).clamp(Self::EPSILON, 2.0 - Self::EPSILON);
+
+        ((2.0 - errors) / errors).log2() * (1.0 - errors)
+    }
+}
+
+/// Kullback criterion divided by its value at the smallest distinguishable sum of errors.
+pub struct NormalizedKullback;
+
+impl NormalizedKullback {
+    const EPSILON: f64 = 1e-2;
+
+    fn kullback(errors: f64) -> f64 {
+        let errors = errors.clamp(Self::EPSILON, 2.0 - Self::EPSILON);
+
+        ((2.0 - errors) / errors).log2() * (1.0 - errors)
+    }
+}
+
+impl Criterion for NormalizedKullback {
+    fn name(&self) -> &'static str {
+        "Normalized Kullback"
+    }
+
+    fn value(&self, c: &Characteristics, _: f64) -> f64 {
+        Self::kullback(c.alpha + c.beta) / Self::kullback(0.0)
+    }
+}
+
+pub struct Shannon;
+
+impl Criterion for Shannon {
+    fn name(&self) -> &'static str {
+        "Shannon"
+    }
+
+    fn value(&self, c: &Characteristics, _: f64) -> f64 {
+        let divisor1 = c.alpha + c.d2;
+        let divisor2 = c.d1 + c.beta;
+
+        1.0 + 0.5
+            * (x_log2(c.alpha / divisor1, 1.0)
+                + x_log2(c.d1 / divisor2, 1.0)
+                + x_log2(c.beta / divisor2, 1.0)
+                + x_log2(c.d2 / divisor1, 1.0))
+    }
+}
+
+/// Sum of errors of the first and the second kind.
+pub struct TotalError;
+
+impl Criterion for TotalError {
+    fn name(&self) -> &'static str {
+        "Total error"
+    }
+
+    fn value(&self, c: &Characteristics, _: f64) -> f64 {
+        c.alpha + c.beta
+    }
+
+    fn lower_is_better(&self) -> bool {
+        true
+    }
+}
+
+pub struct Youden;
+
+impl Criterion for Youden {
+    fn name(&self) -> &'static str {
+        "Youden index"
+    }
+
+    fn value(&self, c: &Characteristics, _: f64) -> f64 {
+        c.d1 + c.d2 - 1.0
+    }
+}
+
+/// Harmonic mean of precision and recall of recognizing the class.
+pub struct FMeasure;
+
+impl Criterion for FMeasure {
+    fn name(&self) -> &'static str {
+        "F-measure"
+    }
+
+    fn value(&self, c: &Characteristics, prior: f64) -> f64 {
+        let true_positive = prior * c.d1;
+        let false_positive = (1.0 - prior) * c.beta;
+        let false_negative = prior * c.alpha;
+        let divisor = 2.0 * true_positive + false_positive + false_negative;
+
+        if divisor > 0.0 {
+            2.0 * true_positive / divisor
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Mutual information between belonging to the class and falling into its container.
+pub struct MutualInformation;
+
+impl Criterion for MutualInformation {
+    fn name(&self) -> &'static str {
+        "Mutual information"
+    }
+
+    fn value(&self, c: &Characteristics, prior: f64) -> f64 {
+        let inside = prior * c.d1 + (1.0 - prior) * c.beta;
+        let outside = prior * c.alpha + (1.0 - prior) * c.d2;
+
+        prior * (x_log2(c.d1, inside) + x_log2(c.alpha, outside))
+            + (1.0 - prior) * (x_log2(c.beta, inside) + x_log2(c.d2, outside))
+    }
+}
+
+/// Selectable criterion.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CriterionKind {
+    Kullback,
+    NormalizedKullback,
+    #[default]
+    Shannon,
+    TotalError,
+    Youden,
+    FMeasure,
+    MutualInformation,
+}
+
+impl CriterionKind {
+    pub const ALL: [CriterionKind; 7] = [
+        CriterionKind::Kullback,
+        CriterionKind::NormalizedKullback,
+        CriterionKind::Shannon,
+        CriterionKind::TotalError,
+        CriterionKind::Youden,
+        CriterionKind::FMeasure,
+        CriterionKind::MutualInformation,
+    ];
+
+    pub fn criterion(self) -> &'static dyn Criterion {
+        match self {
+            CriterionKind::Kullback => &Kullback,
+            CriterionKind::NormalizedKullback => &NormalizedKullback,
+            CriterionKind::Shannon => &Shannon,
+            CriterionKind::TotalError => &TotalError,
+            CriterionKind::Youden => &Youden,
+            CriterionKind::FMeasure => &FMeasure,
+            CriterionKind::MutualInformation => &MutualInformation,
+        }
+    }
+
+    /// Name of the criterion in snake case, used as a column of exported tables.
+    pub fn column(self) -> String {
+        self.to_string().to_lowercase().replace([' ', '-'], "_")
+    }
+
+    /// Position of the criterion in [`CriterionKind::ALL`].
+    pub fn index(self) -> usize {
+        Self::ALL.iter().position(|&kind| kind == self).unwrap()
+    }
+}
+
+impl Display for CriterionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.criterion().name())
+    }
+}
+
+impl std::str::FromStr for CriterionKind {
+    type Err = Error;
+
+    /// Parses the snake case name of a criterion.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.column() == value)
+            .ok_or_else(|| Error::InvalidArgument(format!("Unknown criterion {value}")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn characteristics(alpha: f64, beta: f64) -> Characteristics {
+        Characteristics {
+            d1: 1.0 - alpha,
+            beta,
+            alpha,
+            d2: 1.0 - beta,
+        }
+    }
+
+    fn value(kind: CriterionKind, alpha: f64, beta: f64) -> f64 {
+        kind.criterion().value(&characteristics(alpha, beta), 0.5)
+    }
+
+    proptest! {
+        #[test]
+        fn shannon_is_bounded(alpha in 0.0..=1.0, beta in 0.0..=1.0) {
+            let criteria = value(CriterionKind::Shannon, alpha, beta);
+
+            prop_assert!((-1e-12..=1.0 + 1e-12).contains(&criteria));
+        }
+
+        #[test]
+        fn shannon_is_symmetric_in_errors(alpha in 0.0..=1.0, beta in 0.0..=1.0) {
+            let a = value(CriterionKind::Shannon, alpha, beta);
+            let b = value(CriterionKind::Shannon, beta, alpha);
+
+            prop_assert!((a - b).abs() < 1e-12);
+        }
+
+        #[test]
+        fn kullback_is_symmetric_in_errors(alpha in 0.01..=1.0, beta in 0.01..=1.0) {
+            let a = value(CriterionKind::Kullback, alpha, beta);
+            let b = value(CriterionKind::Kullback, beta, alpha);
+
+            prop_assert!((a - b).abs() < 1e-12);
+        }
+
+        #[test]
+        fn kullback_is_non_negative_for_errors_below_one(alpha in 0.01..0.5, beta in 0.0..0.49) {
+            prop_assert!(value(CriterionKind::Kullback, alpha, beta) >= 0.0);
+        }
+
+        #[test]
+        fn kullback_is_finite(alpha in 0.0..=1.0, beta in 0.0..=1.0) {
+            prop_assert!(value(CriterionKind::Kullback, alpha, beta).is_finite());
+        }
+
+        #[test]
+        fn normalized_kullback_is_at_most_one(alpha in 0.0..=1.0, beta in 0.0..=1.0) {
+            prop_assert!(value(CriterionKind::NormalizedKullback, alpha, beta) <= 1.0 + 1e-12);
+        }
+
+        #[test]
+        fn mutual_information_is_bounded_by_entropy_of_prior(
+            alpha in 0.0..=1.0,
+            beta in 0.0..=1.0,
+            prior in 0.01..0.99,
+        ) {
+            let c = characteristics(alpha, beta);
+            let information = MutualInformation.value(&c, prior);
+            let entropy = -x_log2(prior, 1.0) - x_log2(1.0 - prior, 1.0);
+
+            prop_assert!(information >= -1e-12);
+            prop_assert!(information <= entropy + 1e-12);
+        }
+
+        #[test]
+        fn f_measure_is_bounded(alpha in 0.0..=1.0, beta in 0.0..=1.0, prior in 0.01..0.99) {
+            let f = FMeasure.value(&characteristics(alpha, beta), prior);
+
+            prop_assert!((0.0..=1.0).contains(&f));
+        }
+
+        #[test]
+        fn youden_complements_total_error(alpha in 0.0..=1.0, beta in 0.0..=1.0) {
+            let youden = value(CriterionKind::Youden, alpha, beta);
+            let total_error = value(CriterionKind::TotalError, alpha, beta);
+
+            prop_assert!((youden + total_error - 1.0).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn without_errors_criteria_are_at_their_best() {
+        assert_eq!(value(CriterionKind::Shannon, 0.0, 0.0), 1.0);
+        let kullback = value(CriterionKind::Kullback, 0.0, 0.0);
+        assert!(kullback.is_finite() && kullback > value(CriterionKind::Kullback, 1e-3, 0.0));
+        assert_eq!(value(CriterionKind::NormalizedKullback, 0.0, 0.0), 1.0);
+        assert_eq!(value(CriterionKind::TotalError, 0.0, 0.0), 0.0);
+        assert_eq!(value(CriterionKind::Youden, 0.0, 0.0), 1.0);
+        assert_eq!(value(CriterionKind::FMeasure, 0.0, 0.0), 1.0);
+        assert_eq!(value(CriterionKind::MutualInformation, 0.0, 0.0), 1.0);
+    }
+
+    #[test]
+    fn random_guess_carries_no_information() {
+        assert!(value(CriterionKind::Shannon, 0.5, 0.5).abs() < 1e-12);
+        assert_eq!(value(CriterionKind::Kullback, 0.3, 0.7), 0.0);
+        assert_eq!(value(CriterionKind::Youden, 0.3, 0.7), 0.0);
+        assert!(value(CriterionKind::MutualInformation, 0.3, 0.7).abs() < 1e-12);
+    }
+
+    #[test]
+    fn index_matches_position() {
+        CriterionKind::ALL
+            .iter()
+            .enumerate()
+            .for_each(|(i, kind)| assert_eq!(kind.index(), i));
+    }
+
+    #[test]
+    fn parses_column_names() {
+        CriterionKind::ALL
+            .iter()
+            .for_each(|&kind| assert_eq!(kind.column().parse(), Ok(kind)));
+        assert_eq!("youden_index".parse(), Ok(CriterionKind::Youden));
+        assert!("entropy".parse::<CriterionKind>().is_err());
+    }
+}
@@ -0,0 +1,181 @@
+use std::fmt::Display;
+
+use eframe::egui::{Button, Label, ScrollArea, Ui};
+
+/// Failure of a stage of the training pipeline.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// File could not be read.
+    Io { path: String, message: String },
+    /// File is not a supported image.
+    Decode { path: String, message: String },
+    /// Class has no attributes or realizations.
+    EmptyClass { path: String },
+    /// Class or vector has a different number of attributes than loaded classes.
+    ShapeMismatch { expected: usize, got: usize },
+    /// Class with the same contents has already been loaded.
+    DuplicateClass { name: String },
+    /// Training needs a training class.
+    EmptyTrainingSet,
+    /// Every attribute is excluded, so classes cannot be compared.
+    NoAttributes,
+    /// No parameter values give a working space to the classes.
+    DegenerateCriteria { classes: Vec<usize> },
+    /// Command line argument is invalid.
+    InvalidArgument(String),
+    /// Plots or numeric results could not be saved.
+    Export(String),
+}
+
+impl Error {
+    /// Exit code of the command line run failed with the error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::InvalidArgument(_) => 2,
+            Error::Io { .. } | Error::Export(_) => 3,
+            Error::Decode { .. } => 4,
+            Error::EmptyClass { .. }
+            | Error::ShapeMismatch { .. }
+            | Error::DuplicateClass { .. }
+            | Error::NoAttributes => 5,
+            Error::EmptyTrainingSet => 6,
+            Error::DegenerateCriteria { .. } => 7,
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Io { path, message } => write!(f, "Error: Cannot read {path}: {message}"),
+            Error::Decode { path, message } => write!(f, "Error: Cannot decode {path}: {message}"),
+            Error::EmptyClass { path } => {
+                write!(f, "Error: Class {path} has no attributes or realizations")
+            }
+            Error::ShapeMismatch { expected, got } => {
+                write!(f, "Error: Expected {expected} attributes, got {got}")
+            }
+            Error::DuplicateClass { name } => {
+                write!(f, "Error: Class {name} has already been loaded")
+            }
+            Error::EmptyTrainingSet => write!(f, "Error: No training classes"),
+            Error::NoAttributes => write!(f, "Error: Every attribute is excluded"),
+            Error::DegenerateCriteria { classes } => {
+                let classes: Vec<String> = classes.iter().map(usize::to_string).collect();
+                write!(
+                    f,
+                    "Error: No parameter values give a working space to classes {}",
+                    classes.join(", ")
+                )
+            }
+            Error::InvalidArgument(message) => write!(f, "Error: {message}"),
+            Error::Export(message) => write!(f, "Error: Cannot export {message}"),
+        }
+    }
+}
+
+/// Degenerate result of training that does not stop it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Warning {
+    /// No radius of the class satisfies the working space.
+    NoWorkingSpace { class: usize },
+    /// Classes have the same realizations, such as a class and its duplicate.
+    IdenticalClasses { first: usize, second: usize },
+}
+
+impl Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Warning::NoWorkingSpace { class } => write!(
+                f,
+                "Warning: Class {class} has no working space, exam does not recognize it"
+            ),
+            Warning::IdenticalClasses { first, second } => write!(
+                f,
+                "Warning: Classes {first} and {second} are identical and cannot be told apart"
+            ),
+        }
+    }
+}
+
+/// Warnings about the current training and errors shown to the user until dismissed.
+#[derive(Debug, Default)]
+pub struct Notifications {
+    warnings: Vec<Warning>,
+    errors: Vec<Error>,
+}
+
+impl Notifications {
+    /// Adds the error unless it is already shown.
+    pub fn push(&mut self, error: Error) {
+        if !self.errors.contains(&error) {
+            self.errors.push(error);
+        }
+    }
+
+    /// Replaces warnings with those of the current training.
+    pub fn set_warnings(&mut self, warnings: Vec<Warning>) {
+        self.warnings = warnings;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.warnings.is_empty() && self.errors.is_empty()
+    }
+
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    pub fn errors(&self) -> &[Error] {
+        &self.errors
+    }
+
+    pub fn show(&mut self, ui: &mut Ui) {
+        let mut dismissed = None;
+
+        ScrollArea::vertical().max_height(100.0).show(ui, |ui| {
+            self.warnings().iter().for_each(|warning| {
+                ui.colored_label(ui.visuals().warn_fg_color, warning.to_string());
+            });
+            self.errors().iter().enumerate().for_each(|(i, error)| {
+                ui.horizontal(|ui| {
+                    if ui.add(Button::new("x")).clicked() {
+                        dismissed = Some(i);
+                    }
+                    ui.add(Label::new(error.to_string()));
+                });
+            });
+        });
+
+        if let Some(i) = dismissed {
+            self.errors.remove(i);
+        }
+
+        if self.errors.len() > 1 && ui.add(Button::new("Dismiss all")).clicked() {
+            self.errors.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn repeated_errors_are_shown_once() {
+        let mut notifications = Notifications::default();
+
+        notifications.push(Error::EmptyTrainingSet);
+        notifications.push(Error::ShapeMismatch {
+            expected: 3,
+            got: 2,
+        });
+        notifications.push(Error::EmptyTrainingSet);
+
+        assert_eq!(notifications.errors().len(), 2);
+        assert_eq!(
+            notifications.errors()[1].to_string(),
+            "Error: Expected 3 attributes, got 2"
+        );
+    }
+}
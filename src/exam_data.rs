@@ -1,10 +1,13 @@
 use std::fmt::Display;
 
-use crate::{class_data::TextureData, criteria::Criteria, sk_manager::SKManager};
+use crate::{
+    class_data::TextureData, criteria::Criteria, criterion::CriterionKind, error::Error, shape,
+    sk_manager::SKManager,
+};
 
 pub type ExamRealizationResults = (Vec<usize>, usize);
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum ExamResult {
     Found(usize, ExamRealizationResults),
     Unknown(ExamRealizationResults),
@@ -19,25 +22,48 @@ impl Display for ExamResult {
     }
 }
 
+/// Determines which training class each exam matrix belongs to,
+/// using containers with the smallest optimal radius by `radius_criterion`.
+/// Classes without working space have no container and recognize no realizations.
+///
+/// Fails if exam matrices or reference vectors do not have `attributes` attributes.
+///
+/// # Panics
+///
+/// Panics if there are not as many criterias as reference vectors.
 pub fn exam(
     reference_vectors: &[TextureData],
     exam_matrices: &[TextureData],
     criterias: &[Criteria],
-    realizations: usize,
-) -> Vec<ExamResult> {
+    radius_criterion: CriterionKind,
+    attributes: usize,
+) -> Result<Vec<ExamResult>, Error> {
+    assert_eq!(criterias.len(), reference_vectors.len());
+    shape::check_attributes(exam_matrices, reference_vectors, attributes)?;
+
     exam_matrices
         .iter()
         .map(|matrix| {
+            let shape = matrix.shape();
+            shape.validate(&matrix.bytes);
+            let realizations = shape.realizations;
+
             let distances: Vec<Vec<f64>> = reference_vectors
                 .iter()
                 .enumerate()
                 .map(|(i, center)| {
-                    SKManager::distances_between(&matrix.bytes, &center.bytes)
-                        .iter()
-                        .map(|x| 1.0 - *x as f64 / criterias[i].min_radius())
-                        .collect()
+                    let distances = SKManager::distances_between(&matrix.bytes, &center.bytes)?;
+
+                    Ok(match criterias[i].min_radius(radius_criterion) {
+                        // A container of radius 0 holds realizations equal to the centre
+                        Some(radius) => distances
+                            .iter()
+                            .map(|&x| 1.0 - x as f64 / radius.max(f64::EPSILON))
+                            .collect(),
+                        None => vec![f64::NEG_INFINITY; distances.len()],
+                    })
                 })
-                .collect();
+                .collect::<Result<_, Error>>()?;
 
             let results: Vec<usize> = distances
                 .iter()
@@ -69,11 +95,11 @@ pub fn exam(
                 .map(|(i, _)| i)
                 .collect();
 
-            if classes.len() == 1 {
+            Ok(if classes.len() == 1 {
                 ExamResult::Found(classes[0], statistics)
             } else {
                 ExamResult::Unknown(statistics)
-            }
+            })
         })
         .collect()
 }
@@ -0,0 +1,656 @@
+use std::{fmt::Write, path::Path};
+
+use eframe::{
+    egui::{Button, DragValue, Label, TextEdit, Ui},
+    epaint::{text::FontDefinitions, Color32},
+};
+
+use crate::error::Error;
+
+pub const PALETTE: [Color32; 6] = [
+    Color32::from_rgb(31, 119, 180),
+    Color32::from_rgb(255, 127, 14),
+    Color32::from_rgb(44, 160, 44),
+    Color32::from_rgb(214, 39, 40),
+    Color32::from_rgb(148, 103, 189),
+    Color32::from_rgb(140, 86, 75),
+];
+
+const MARGIN_LEFT: f64 = 60.0;
+const MARGIN_RIGHT: f64 = 15.0;
+const MARGIN_TOP: f64 = 30.0;
+const MARGIN_BOTTOM: f64 = 30.0;
+const FONT_SIZE: f64 = 12.0;
+
+/// A plot line. Non-finite points split the line into segments.
+pub struct FigureLine {
+    pub name: String,
+    pub points: Vec<[f64; 2]>,
+    pub color: Option<Color32>,
+    /// Fills the area between the line and this value of y.
+    pub fill: Option<f64>,
+}
+
+impl FigureLine {
+    pub fn new(name: impl Into<String>, points: Vec<[f64; 2]>) -> Self {
+        Self {
+            name: name.into(),
+            points,
+            color: None,
+            fill: None,
+        }
+    }
+
+    pub fn from_ys(name: impl Into<String>, ys: &[f64]) -> Self {
+        Self::new(
+            name,
+            ys.iter().enumerate().map(|(i, &y)| [i as f64, y]).collect(),
+        )
+    }
+
+    pub fn fill(mut self, y: f64) -> Self {
+        self.fill = Some(y);
+        self
+    }
+}
+
+/// A circle with a radius in plot coordinates.
+pub struct FigureCircle {
+    pub center: [f64; 2],
+    pub radius: f64,
+    pub color: Color32,
+    pub filled: bool,
+}
+
+/// Shaded ranges of x spanning the whole height of a panel.
+pub struct FigureRegion {
+    pub name: String,
+    pub color: Color32,
+    pub intervals: Vec<[f64; 2]>,
+}
+
+/// One plot of a figure with its own axes.
+#[derive(Default)]
+pub struct Panel {
+    pub title: String,
+    pub lines: Vec<FigureLine>,
+    pub circles: Vec<FigureCircle>,
+    pub regions: Vec<FigureRegion>,
+    /// Keeps the same scale on both axes, so circles stay round.
+    pub equal_aspect: bool,
+}
+
+impl Panel {
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn line(mut self, line: FigureLine) -> Self {
+        self.lines.push(line);
+        self
+    }
+
+    pub fn circle(mut self, center: [f64; 2], radius: f64, color: Color32, filled: bool) -> Self {
+        self.circles.push(FigureCircle {
+            center,
+            radius,
+            color,
+            filled,
+        });
+        self
+    }
+
+    pub fn region(
+        mut self,
+        name: impl Into<String>,
+        color: Color32,
+        intervals: Vec<[f64; 2]>,
+    ) -> Self {
+        self.regions.push(FigureRegion {
+            name: name.into(),
+            color,
+            intervals,
+        });
+        self
+    }
+
+    pub fn equal_aspect(mut self) -> Self {
+        self.equal_aspect = true;
+        self
+    }
+
+    fn bounds(&self, width: f64, height: f64) -> Bounds {
+        let points = self
+            .lines
+            .iter()
+            .flat_map(|line| {
+                line.points
+                    .iter()
+                    .copied()
+                    .chain(line.fill.iter().flat_map(|&y| {
+                        line.points
+                            .iter()
+                            .filter(|p| p[0].is_finite())
+                            .map(move |p| [p[0], y])
+                    }))
+            })
+            .chain(self.circles.iter().flat_map(|c| {
+                [
+                    [c.center[0] - c.radius, c.center[1] - c.radius],
+                    [c.center[0] + c.radius, c.center[1] + c.radius],
+                ]
+            }))
+            .filter(|p| p[0].is_finite() && p[1].is_finite());
+
+        let mut bounds = Bounds::from_points(points);
+
+        if self.equal_aspect {
+            bounds.equalize(width, height);
+        }
+
+        bounds
+    }
+}
+
+/// Plot data that can be rendered without a GUI.
+#[derive(Default)]
+pub struct Figure {
+    pub panels: Vec<Panel>,
+    /// Number of panels in a row, all panels are in one row if zero.
+    pub columns: usize,
+}
+
+impl Figure {
+    pub fn new(panels: Vec<Panel>) -> Self {
+        Self { panels, columns: 0 }
+    }
+
+    pub fn columns(mut self, columns: usize) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    /// Renders the figure as an SVG document of the given size in pixels.
+    pub fn to_svg(&self, width: u32, height: u32) -> String {
+        let mut svg = String::new();
+
+        let _ = write!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="sans-serif" font-size="{FONT_SIZE}">"#
+        );
+        let _ = write!(
+            svg,
+            r#"<rect width="{width}" height="{height}" fill="white"/>"#
+        );
+
+        let columns = match self.columns {
+            0 => self.panels.len().max(1),
+            columns => columns,
+        };
+        let rows = self.panels.len().div_ceil(columns).max(1);
+        let panel_width = width as f64 / columns as f64;
+        let panel_height = height as f64 / rows as f64;
+
+        self.panels.iter().enumerate().for_each(|(i, panel)| {
+            Self::write_panel(
+                &mut svg,
+                panel,
+                [
+                    (i % columns) as f64 * panel_width,
+                    (i / columns) as f64 * panel_height,
+                ],
+                panel_width,
+                panel_height,
+            );
+        });
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Renders the figure as a PNG image of the given size in pixels.
+    pub fn to_png(&self, width: u32, height: u32) -> Result<Vec<u8>, String> {
+        let mut options = resvg::usvg::Options::default();
+        let fonts = options.fontdb_mut();
+
+        FontDefinitions::default()
+            .font_data
+            .into_values()
+            .for_each(|data| fonts.load_font_data(data.font.into_owned()));
+
+        let family = fonts
+            .faces()
+            .find(|face| face.post_script_name.starts_with("Ubuntu"))
+            .and_then(|face| face.families.first())
+            .map(|family| family.0.clone());
+
+        if let Some(family) = family {
+            fonts.set_sans_serif_family(family);
+        }
+
+        let tree = resvg::usvg::Tree::from_str(&self.to_svg(width, height), &options)
+            .map_err(|err| err.to_string())?;
+
+        let mut pixmap = resvg::tiny_skia::Pixmap::new(width, height)
+            .ok_or_else(|| format!("Invalid image size {width}x{height}"))?;
+
+        resvg::render(
+            &tree,
+            resvg::tiny_skia::Transform::default(),
+            &mut pixmap.as_mut(),
+        );
+
+        pixmap.encode_png().map_err(|err| err.to_string())
+    }
+
+    /// Saves the figure as SVG or PNG depending on the extension of `path`.
+    pub fn save(&self, path: &Path, width: u32, height: u32) -> Result<(), Error> {
+        let export = |message: String| Error::Export(format!("{}: {message}", path.display()));
+
+        let bytes = match path.extension().and_then(|e| e.to_str()) {
+            Some("svg") => self.to_svg(width, height).into_bytes(),
+            Some("png") => self.to_png(width, height).map_err(export)?,
+            _ => return Err(export("File extension should be svg or png".to_owned())),
+        };
+
+        std::fs::write(path, bytes).map_err(|err| export(err.to_string()))
+    }
+
+    fn write_panel(
+        svg: &mut String,
+        panel: &Panel,
+        [left, top]: [f64; 2],
+        width: f64,
+        height: f64,
+    ) {
+        let plot_width = (width - MARGIN_LEFT - MARGIN_RIGHT).max(1.0);
+        let plot_height = (height - MARGIN_TOP - MARGIN_BOTTOM).max(1.0);
+        let bounds = panel.bounds(plot_width, plot_height);
+
+        let origin_x = left + MARGIN_LEFT;
+        let plot_top = top + MARGIN_TOP;
+        let origin_y = plot_top + plot_height;
+        let scale_x = plot_width / (bounds.max[0] - bounds.min[0]);
+        let scale_y = plot_height / (bounds.max[1] - bounds.min[1]);
+        let x = |value: f64| origin_x + (value - bounds.min[0]) * scale_x;
+        let y = |value: f64| origin_y - (value - bounds.min[1]) * scale_y;
+
+        let _ = write!(
+            svg,
+            r#"<text x="{}" y="{}" text-anchor="middle" font-weight="bold">{}</text>"#,
+            origin_x + plot_width / 2.0,
+            plot_top - 10.0,
+            escape(&panel.title)
+        );
+
+        ticks(bounds.min[0], bounds.max[0]).into_iter().for_each(|t| {
+            let _ = write!(
+                svg,
+                r##"<line x1="{0}" y1="{1}" x2="{0}" y2="{2}" stroke="#e0e0e0"/><text x="{0}" y="{3}" text-anchor="middle">{4}</text>"##,
+                x(t),
+                plot_top,
+                origin_y,
+                origin_y + FONT_SIZE + 4.0,
+                format_tick(t)
+            );
+        });
+
+        ticks(bounds.min[1], bounds.max[1]).into_iter().for_each(|t| {
+            let _ = write!(
+                svg,
+                r##"<line x1="{0}" y1="{1}" x2="{2}" y2="{1}" stroke="#e0e0e0"/><text x="{3}" y="{4}" text-anchor="end">{5}</text>"##,
+                origin_x,
+                y(t),
+                origin_x + plot_width,
+                origin_x - 4.0,
+                y(t) + FONT_SIZE / 3.0,
+                format_tick(t)
+            );
+        });
+
+        let _ = write!(
+            svg,
+            r#"<svg x="{origin_x}" y="{plot_top}" width="{plot_width}" height="{plot_height}" viewBox="{origin_x} {plot_top} {plot_width} {plot_height}">"#
+        );
+
+        panel.regions.iter().for_each(|region| {
+            let color = hex(region.color);
+
+            region.intervals.iter().for_each(|&[start, end]| {
+                let _ = write!(
+                    svg,
+                    r#"<rect x="{}" y="{plot_top}" width="{}" height="{plot_height}" fill="{color}" fill-opacity="0.15" stroke="none"/>"#,
+                    x(start),
+                    (end - start) * scale_x
+                );
+            });
+        });
+
+        panel.circles.iter().for_each(|circle| {
+            let color = hex(circle.color);
+            let fill = if circle.filled {
+                color.as_str()
+            } else {
+                "none"
+            };
+            let _ = write!(
+                svg,
+                r#"<circle cx="{}" cy="{}" r="{}" fill="{fill}" stroke="{color}"/>"#,
+                x(circle.center[0]),
+                y(circle.center[1]),
+                circle.radius * scale_x
+            );
+        });
+
+        panel.lines.iter().enumerate().for_each(|(i, line)| {
+            let color = hex(line.color.unwrap_or(PALETTE[i % PALETTE.len()]));
+
+            segments(&line.points).into_iter().for_each(|segment| {
+                let points: Vec<String> = segment
+                    .iter()
+                    .map(|p| format!("{},{}", x(p[0]), y(p[1])))
+                    .collect();
+
+                if let Some(fill) = line.fill {
+                    let first = segment[0][0];
+                    let last = segment[segment.len() - 1][0];
+                    let _ = write!(
+                        svg,
+                        r#"<polygon points="{} {},{} {},{}" fill="{color}" fill-opacity="0.2" stroke="none"/>"#,
+                        points.join(" "),
+                        x(last),
+                        y(fill),
+                        x(first),
+                        y(fill)
+                    );
+                }
+
+                let _ = write!(
+                    svg,
+                    r#"<polyline points="{}" fill="none" stroke="{color}" stroke-width="1.5"/>"#,
+                    points.join(" ")
+                );
+            });
+        });
+
+        svg.push_str("</svg>");
+
+        let _ = write!(
+            svg,
+            r#"<rect x="{origin_x}" y="{plot_top}" width="{plot_width}" height="{plot_height}" fill="none" stroke="black"/>"#
+        );
+
+        let legend_x = origin_x + plot_width - 10.0;
+        let legend_y = |row: usize| plot_top + 15.0 + row as f64 * (FONT_SIZE + 4.0);
+        let lines = panel
+            .lines
+            .iter()
+            .enumerate()
+            .filter(|(_, line)| !line.name.is_empty());
+
+        lines.clone().enumerate().for_each(|(row, (i, line))| {
+            let color = hex(line.color.unwrap_or(PALETTE[i % PALETTE.len()]));
+
+            let _ = write!(
+                svg,
+                r#"<line x1="{}" y1="{}" x2="{}" y2="{1}" stroke="{color}" stroke-width="2"/><text x="{}" y="{}" text-anchor="end">{}</text>"#,
+                legend_x - 20.0,
+                legend_y(row) - FONT_SIZE / 3.0,
+                legend_x,
+                legend_x - 25.0,
+                legend_y(row),
+                escape(&line.name)
+            );
+        });
+
+        panel
+            .regions
+            .iter()
+            .enumerate()
+            .for_each(|(i, region)| {
+                let row = lines.clone().count() + i;
+
+                let _ = write!(
+                    svg,
+                    r#"<rect x="{}" y="{}" width="20" height="{}" fill="{}" fill-opacity="0.3"/><text x="{}" y="{}" text-anchor="end">{}</text>"#,
+                    legend_x - 20.0,
+                    legend_y(row) - FONT_SIZE * 2.0 / 3.0,
+                    FONT_SIZE * 2.0 / 3.0,
+                    hex(region.color),
+                    legend_x - 25.0,
+                    legend_y(row),
+                    escape(&region.name)
+                );
+            });
+    }
+}
+
+/// Area of plot coordinates shown in a panel.
+struct Bounds {
+    min: [f64; 2],
+    max: [f64; 2],
+}
+
+impl Bounds {
+    fn from_points(points: impl Iterator<Item = [f64; 2]>) -> Self {
+        let mut bounds = Self {
+            min: [f64::INFINITY; 2],
+            max: [f64::NEG_INFINITY; 2],
+        };
+
+        points.for_each(|p| {
+            (0..2).for_each(|axis| {
+                bounds.min[axis] = bounds.min[axis].min(p[axis]);
+                bounds.max[axis] = bounds.max[axis].max(p[axis]);
+            });
+        });
+
+        (0..2).for_each(|axis| {
+            if !bounds.min[axis].is_finite() {
+                bounds.min[axis] = 0.0;
+                bounds.max[axis] = 1.0;
+            } else if bounds.min[axis] == bounds.max[axis] {
+                bounds.min[axis] -= 0.5;
+                bounds.max[axis] += 0.5;
+            }
+        });
+
+        bounds
+    }
+
+    /// Extends the shorter axis so both axes have the same scale in a plot of the given size.
+    fn equalize(&mut self, width: f64, height: f64) {
+        let scale = f64::min(
+            width / (self.max[0] - self.min[0]),
+            height / (self.max[1] - self.min[1]),
+        );
+
+        [width, height].iter().enumerate().for_each(|(axis, size)| {
+            let center = (self.min[axis] + self.max[axis]) / 2.0;
+            let half = size / scale / 2.0;
+            self.min[axis] = center - half;
+            self.max[axis] = center + half;
+        });
+    }
+}
+
+/// Returns values between `min` and `max` at a round step.
+fn ticks(min: f64, max: f64) -> Vec<f64> {
+    let raw_step = (max - min) / 5.0;
+    let magnitude = 10f64.powf(raw_step.log10().floor());
+    let step = [1.0, 2.0, 5.0, 10.0]
+        .iter()
+        .map(|m| m * magnitude)
+        .find(|&step| step >= raw_step)
+        .unwrap_or(raw_step);
+
+    let first = (min / step).ceil() as i64;
+    let last = (max / step).floor() as i64;
+
+    (first..=last).map(|i| i as f64 * step).collect()
+}
+
+fn format_tick(value: f64) -> String {
+    let rounded = (value * 1e6).round() / 1e6;
+
+    if rounded == 0.0 {
+        "0".to_owned()
+    } else {
+        rounded.to_string()
+    }
+}
+
+/// Splits points into runs of finite points.
+fn segments(points: &[[f64; 2]]) -> Vec<&[[f64; 2]]> {
+    points
+        .split(|p| !p[0].is_finite() || !p[1].is_finite())
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+fn hex(color: Color32) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r(), color.g(), color.b())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Data that can be plotted as a [`Figure`].
+pub trait ToFigure {
+    fn figure(&self) -> Figure;
+}
+
+/// Controls of a window for saving its plot to a file.
+pub struct FigureExport {
+    path: String,
+    width: u32,
+    height: u32,
+    message: Option<String>,
+}
+
+impl Default for FigureExport {
+    fn default() -> Self {
+        Self {
+            path: String::new(),
+            width: 1200,
+            height: 800,
+            message: None,
+        }
+    }
+}
+
+impl FigureExport {
+    pub fn show(&mut self, figure: &impl ToFigure, ui: &mut Ui) {
+        ui.horizontal(|ui| {
+            ui.add(TextEdit::singleline(&mut self.path).hint_text("plot.svg or plot.png"));
+            ui.add(DragValue::new(&mut self.width).clamp_range(100..=10000));
+            ui.add(Label::new("x"));
+            ui.add(DragValue::new(&mut self.height).clamp_range(100..=10000));
+
+            if ui.add(Button::new("Export")).clicked() {
+                self.message = Some(
+                    match figure
+                        .figure()
+                        .save(Path::new(&self.path), self.width, self.height)
+                    {
+                        Ok(()) => format!("Saved to {}", self.path),
+                        Err(err) => err.to_string(),
+                    },
+                );
+            }
+
+            if let Some(message) = self.message.clone() {
+                ui.add(Label::new(message));
+
+                if ui.add(Button::new("x")).clicked() {
+                    self.message = None;
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn figure() -> Figure {
+        Figure::new(vec![
+            Panel::new("Lines")
+                .line(FigureLine::from_ys(
+                    "A & B",
+                    &[0.0, 1.0, f64::INFINITY, 3.0, 2.0],
+                ))
+                .line(FigureLine::from_ys("Filled", &[1.0, 2.0]).fill(0.0)),
+            Panel::new("Circles")
+                .circle([0.0, 0.0], 5.0, Color32::GREEN, false)
+                .circle([10.0, 0.0], 1.0, Color32::RED, true)
+                .equal_aspect(),
+        ])
+    }
+
+    #[test]
+    fn non_finite_points_split_lines() {
+        let points = [[0.0, 0.0], [1.0, f64::NAN], [2.0, 1.0], [3.0, 2.0]];
+
+        assert_eq!(segments(&points), vec![&points[..1], &points[2..]]);
+    }
+
+    #[test]
+    fn ticks_are_round() {
+        assert_eq!(ticks(0.0, 100.0), vec![0.0, 20.0, 40.0, 60.0, 80.0, 100.0]);
+        assert_eq!(
+            ticks(-0.3, 0.3)
+                .into_iter()
+                .map(format_tick)
+                .collect::<Vec<String>>(),
+            ["-0.2", "0", "0.2"]
+        );
+    }
+
+    #[test]
+    fn equal_aspect_has_same_scale_on_both_axes() {
+        let mut bounds = Bounds::from_points([[0.0, 0.0], [10.0, 2.0]].into_iter());
+        bounds.equalize(400.0, 200.0);
+
+        let scale_x = 400.0 / (bounds.max[0] - bounds.min[0]);
+        let scale_y = 200.0 / (bounds.max[1] - bounds.min[1]);
+
+        assert!((scale_x - scale_y).abs() < 1e-9);
+    }
+
+    #[test]
+    fn svg_contains_every_panel_and_escaped_names() {
+        let svg = figure().to_svg(800, 400);
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.contains(">Lines</text>"));
+        assert!(svg.contains(">Circles</text>"));
+        assert!(svg.contains(">A &amp; B</text>"));
+        assert_eq!(svg.matches("<circle").count(), 2);
+        assert_eq!(svg.matches("<polygon").count(), 1);
+    }
+
+    #[test]
+    fn png_has_requested_size() {
+        let png = figure().to_png(320, 240).unwrap();
+
+        assert_eq!(&png[1..4], b"PNG");
+        assert_eq!(&png[16..24], &[0, 0, 1, 64, 0, 0, 0, 240]);
+    }
+
+    #[test]
+    fn unknown_extension_is_rejected() {
+        assert!(figure()
+            .save(Path::new("plot.jpg"), 100, 100)
+            .unwrap_err()
+            .to_string()
+            .contains("svg or png"));
+    }
+}
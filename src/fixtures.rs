@@ -0,0 +1,44 @@
+use eframe::{egui::Context, epaint::ColorImage};
+
+use crate::{class_data::TextureData, shape::Shape};
+
+/// Shape of the bundled class images.
+pub const SHAPE: Shape = Shape {
+    attributes: 100,
+    realizations: 100,
+};
+
+/// Loads the top-left `shape` part of a bundled class image.
+pub fn load_cropped(name: &str, shape: Shape, ctx: &Context) -> TextureData {
+    let luma = image::open(format!("classes/{name}")).unwrap().to_luma8();
+    let cropped = image::imageops::crop_imm(
+        &luma,
+        0,
+        0,
+        shape.attributes as u32,
+        shape.realizations as u32,
+    )
+    .to_image();
+
+    let bytes = cropped.into_raw();
+    let texture = ctx.load_texture(
+        name,
+        ColorImage::from_gray(shape.image_size(), &bytes),
+        Default::default(),
+    );
+
+    TextureData::new(bytes, texture)
+}
+
+/// Loads a bundled class image.
+pub fn load(name: &str, ctx: &Context) -> TextureData {
+    load_cropped(name, SHAPE, ctx)
+}
+
+/// Formats a binary vector as a string of zeros and ones.
+pub fn bits(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&byte| if byte == u8::MAX { '1' } else { '0' })
+        .collect()
+}
@@ -0,0 +1,149 @@
+use eframe::egui::Ui;
+use egui_plot::{Bar, BarChart, Legend, Plot, VLine};
+
+use crate::{
+    draw::Show,
+    figure::{Figure, FigureLine, Panel, ToFigure, PALETTE},
+    table::{Table, ToTables},
+};
+
+/// Distributions of distances from realizations to the centre of a class.
+#[derive(Debug, Default)]
+pub struct Histogram {
+    pub class: usize,
+    /// Number of realizations of the class at every distance to its centre.
+    pub own: Vec<usize>,
+    /// Number of realizations of other classes at every distance to the centre.
+    pub neighbours: Vec<usize>,
+    /// Radius of the optimal container, `None` without working space.
+    pub radius: Option<f64>,
+}
+
+impl Histogram {
+    /// Counts `distances[class]` from realizations of every class to the centre of `class`.
+    pub fn new(class: usize, distances: &[Vec<u32>], radius: Option<f64>) -> Self {
+        let len = distances
+            .iter()
+            .flatten()
+            .max()
+            .map_or(0, |&max| max as usize + 1);
+
+        let count = |distances: &mut dyn Iterator<Item = &u32>| {
+            let mut counts = vec![0; len];
+            distances.for_each(|&distance| counts[distance as usize] += 1);
+            counts
+        };
+
+        Self {
+            class,
+            own: count(&mut distances[class].iter()),
+            neighbours: count(
+                &mut distances
+                    .iter()
+                    .enumerate()
+                    .filter(|&(i, _)| i != class)
+                    .flat_map(|(_, distances)| distances),
+            ),
+            radius,
+        }
+    }
+
+    fn bars(counts: &[usize]) -> Vec<Bar> {
+        counts
+            .iter()
+            .enumerate()
+            .filter(|(_, &count)| count > 0)
+            .map(|(distance, &count)| Bar::new(distance as f64, count as f64).width(1.0))
+            .collect()
+    }
+
+    /// Outline of the histogram as steps around every distance.
+    fn steps(counts: &[usize]) -> Vec<[f64; 2]> {
+        counts
+            .iter()
+            .enumerate()
+            .flat_map(|(distance, &count)| {
+                let x = distance as f64;
+                [[x - 0.5, count as f64], [x + 0.5, count as f64]]
+            })
+            .collect()
+    }
+}
+
+impl Show for Histogram {
+    fn show(&self, ui: &mut Ui) {
+        Plot::new(ui.id().with("Histogram"))
+            .legend(Legend::default())
+            .auto_bounds_x()
+            .auto_bounds_y()
+            .show(ui, |ui| {
+                ui.bar_chart(
+                    BarChart::new(Self::bars(&self.own))
+                        .name("Realizations")
+                        .color(PALETTE[0]),
+                );
+                ui.bar_chart(
+                    BarChart::new(Self::bars(&self.neighbours))
+                        .name("Neighbour realizations")
+                        .color(PALETTE[1]),
+                );
+
+                if let Some(radius) = self.radius {
+                    ui.vline(VLine::new(radius).name("Radius").color(PALETTE[2]));
+                }
+            });
+    }
+}
+
+impl ToFigure for Histogram {
+    fn figure(&self) -> Figure {
+        let mut own = FigureLine::new("Realizations", Self::steps(&self.own)).fill(0.0);
+        own.color = Some(PALETTE[0]);
+        let mut neighbours =
+            FigureLine::new("Neighbour realizations", Self::steps(&self.neighbours)).fill(0.0);
+        neighbours.color = Some(PALETTE[1]);
+
+        let mut panel = Panel::new(format!("Distances to centre of class {}", self.class))
+            .line(own)
+            .line(neighbours);
+
+        if let Some(radius) = self.radius {
+            let height = self.own.iter().chain(&self.neighbours).max().copied();
+            let mut line = FigureLine::new(
+                "Radius",
+                vec![[radius, 0.0], [radius, height.unwrap_or_default() as f64]],
+            );
+            line.color = Some(PALETTE[2]);
+            panel = panel.line(line);
+        }
+
+        Figure::new(vec![panel])
+    }
+}
+
+impl ToTables for Histogram {
+    fn tables(&self) -> Vec<Table> {
+        let mut table = Table::new("histogram", &["distance", "own", "neighbours"]);
+
+        self.own.iter().zip(&self.neighbours).enumerate().for_each(
+            |(distance, (&own, &neighbours))| {
+                table.push(vec![distance.into(), own.into(), neighbours.into()]);
+            },
+        );
+
+        vec![table]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distances_of_other_classes_are_pooled() {
+        let histogram = Histogram::new(1, &[vec![4, 2], vec![0, 1, 1], vec![2]], Some(1.0));
+
+        assert_eq!(histogram.own, [1, 2, 0, 0, 0]);
+        assert_eq!(histogram.neighbours, [0, 0, 2, 0, 1]);
+    }
+}
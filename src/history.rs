@@ -0,0 +1,105 @@
+use eframe::egui::{Key, KeyboardShortcut, Modifiers};
+
+use crate::{class_data::TextureData, class_manager::CorridorBase};
+
+pub const UNDO: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Z);
+pub const REDO: KeyboardShortcut =
+    KeyboardShortcut::new(Modifiers::COMMAND.plus(Modifiers::SHIFT), Key::Z);
+pub const REDO_Y: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::Y);
+
+/// Training state restored by undo and redo.
+#[derive(Clone)]
+pub struct TrainingState {
+    pub classes: Vec<TextureData>,
+    pub exam_classes: Vec<TextureData>,
+    pub selected_class: usize,
+    pub corridor_base: CorridorBase,
+    pub prior_weights: Vec<f64>,
+    pub delta: f64,
+    pub attribute_deltas: Vec<Option<f64>>,
+}
+
+/// States before recorded changes, most recent last.
+pub struct History<T> {
+    undo: Vec<T>,
+    redo: Vec<T>,
+    /// Largest number of states kept for undo.
+    limit: usize,
+}
+
+impl<T> Default for History<T> {
+    fn default() -> Self {
+        Self::new(100)
+    }
+}
+
+impl<T> History<T> {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            limit,
+        }
+    }
+
+    /// Records the state before a change, forgetting undone changes.
+    pub fn record(&mut self, state: T) {
+        self.undo.push(state);
+        if self.undo.len() > self.limit {
+            self.undo.remove(0);
+        }
+        self.redo.clear();
+    }
+
+    /// Returns the state before the last change, keeping `current` for redo.
+    pub fn undo(&mut self, current: T) -> Option<T> {
+        let state = self.undo.pop()?;
+        self.redo.push(current);
+        Some(state)
+    }
+
+    /// Returns the state of the last undone change, keeping `current` for undo.
+    pub fn redo(&mut self, current: T) -> Option<T> {
+        let state = self.redo.pop()?;
+        self.undo.push(current);
+        Some(state)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changes_are_undone_and_redone() {
+        let mut history = History::new(2);
+        let mut state = 0;
+
+        for next in 1..=3 {
+            history.record(state);
+            state = next;
+        }
+
+        state = history.undo(state).unwrap();
+        assert_eq!(state, 2);
+        state = history.undo(state).unwrap();
+        assert_eq!(state, 1);
+        // Only the last two changes are kept
+        assert_eq!(history.undo(state), None);
+
+        state = history.redo(state).unwrap();
+        assert_eq!(state, 2);
+
+        history.record(state);
+        assert!(!history.can_redo());
+        assert!(history.can_undo());
+    }
+}
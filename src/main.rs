@@ -1,28 +1,71 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")] // hide console window on Windows in release
+mod attributes;
+mod base_comparison;
 mod binary_representation;
 mod class_data;
 mod class_loader;
 mod class_manager;
+mod cli;
 mod corridor;
 mod criteria;
+mod criterion;
 mod draw;
+mod error;
 mod exam_data;
+mod figure;
+#[cfg(test)]
+mod fixtures;
+mod histogram;
+mod history;
+mod matrix_view;
 mod optimization_results;
+mod optimizer;
+mod projection;
+mod quality;
+mod settings;
+mod shape;
 mod sk;
 mod sk_manager;
+mod table;
+#[cfg(test)]
+mod tests;
+mod working_space;
 
+use base_comparison::{BaseCandidate, BaseComparison};
+use class_data::TextureData;
 use class_loader::ClassLoader;
-use class_manager::ClassManager;
+use class_manager::{ClassManager, ClassOperation, CorridorBase};
 use corridor::Corridor;
 use criteria::Criteria;
+use criterion::CriterionKind;
 use draw::Show;
 
 use eframe::egui;
+use error::{Error, Notifications, Warning};
 use exam_data::ExamResult;
+use figure::FigureExport;
+use histogram::Histogram;
+use history::{History, TrainingState};
+use matrix_view::MatrixView;
 use optimization_results::OptimizationResults;
+use optimizer::{
+    Evaluation, MeanCriterion, Objective, OptimizationLog, OptimizerControls, Parameter,
+    ParameterSpace, Strategy,
+};
+use projection::Projection;
+use quality::QualityTable;
+use settings::Settings;
 use sk_manager::SKManager;
+use table::{DataExport, Table, ToTables};
 
 fn main() -> Result<(), eframe::Error> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    if !args.is_empty() {
+        cli::attach_console();
+        std::process::exit(cli::run(args));
+    }
+
     let options = eframe::NativeOptions {
         ..Default::default()
     };
@@ -41,16 +84,29 @@ fn main() -> Result<(), eframe::Error> {
 
 #[derive(Default)]
 struct MyApp {
-    delta: u8,
+    delta: f64,
     corridor: Corridor,
     widget_stauses: std::collections::HashMap<String, bool>,
     exam_data: Vec<ExamResult>,
     optimization_results: Option<OptimizationResults>,
+    optimizer: OptimizerControls,
+    optimization_log: Option<OptimizationLog>,
+    base_comparison: Option<BaseComparison>,
     class_manager: ClassManager,
     exam_class_manager: ClassManager,
     class_loader: ClassLoader,
     sk_manager: SKManager,
+    sk_layers: sk::Layers,
+    projection: Projection,
+    histograms: Vec<Histogram>,
+    matrix_view: MatrixView,
+    history: History<TrainingState>,
     criterias: Vec<Criteria>,
+    settings: Settings,
+    quality_table: QualityTable,
+    figure_exports: std::collections::HashMap<String, FigureExport>,
+    data_export: DataExport,
+    notifications: Notifications,
 }
 
 impl eframe::App for MyApp {
@@ -61,6 +117,14 @@ impl eframe::App for MyApp {
             ..Default::default()
         };
 
+        if ctx.input_mut(|i| {
+            i.consume_shortcut(&history::REDO) || i.consume_shortcut(&history::REDO_Y)
+        }) {
+            self.redo(ctx);
+        } else if ctx.input_mut(|i| i.consume_shortcut(&history::UNDO)) {
+            self.undo(ctx);
+        }
+
         egui::TopBottomPanel::top("Stages")
             .frame(frame)
             .show(ctx, |ui| {
@@ -70,38 +134,37 @@ impl eframe::App for MyApp {
         egui::TopBottomPanel::bottom(egui::Id::new("Loader"))
             .frame(frame)
             .show(ctx, |ui| {
-                if let Some(data) = self
-                    .class_loader
-                    .show(&self.class_manager, &self.exam_class_manager, ui)
-                    .loaded()
-                {
-                    if self.class_manager.classes.is_empty() {
-                        self.corridor = Corridor::new(&data.bytes, self.class_loader.size)
-                    }
-                    match self.class_loader.class_type {
-                        class_loader::ClassType::Training => {
-                            self.class_manager.add_class(data.clone());
-                            self.recalculate(ctx);
-                        }
-                        class_loader::ClassType::Exam => {
-                            self.exam_class_manager.add_class(data.clone());
-                            self.exam_class_manager
-                                .recalculate_binary_representation(&self.corridor.allowances, ctx);
-                            self.exam();
-                        }
+                let loaded_classes =
+                    self.class_manager.classes.len() + self.exam_class_manager.classes.len();
+
+                if let Some(loaded) = self.class_loader.show(loaded_classes, ui).loaded() {
+                    if let Err(err) = loaded.clone().and_then(|data| self.add_class(data, ctx)) {
+                        self.notifications.push(err);
                     }
                 }
             });
 
+        if !self.notifications.is_empty() {
+            egui::TopBottomPanel::bottom(egui::Id::new("Notifications"))
+                .frame(frame)
+                .show(ctx, |ui| {
+                    self.notifications.show(ui);
+                });
+        }
+
         egui::CentralPanel::default().frame(frame).show(ctx, |_| {
             if *self.widget_stauses.get("Criteria").unwrap_or(&false) {
                 self.criterias.iter().enumerate().for_each(|(i, criteria)| {
                     egui::Window::new(format!("Criteria {i}"))
                         .id(egui::Id::new(format!("Criteria{i}")))
-                        .default_size(egui::vec2(250.0, 200.0))
-                        .min_width(400.0)
-                        .min_height(150.0)
+                        .default_size(egui::vec2(800.0, 400.0))
+                        .min_width(600.0)
+                        .min_height(300.0)
                         .show(ctx, |ui| {
+                            self.figure_exports
+                                .entry(format!("Criteria{i}"))
+                                .or_default()
+                                .show(criteria, ui);
                             criteria.show(ui);
                         });
                 });
@@ -115,16 +178,80 @@ impl eframe::App for MyApp {
                         .min_width(250.0)
                         .min_height(200.0)
                         .show(ctx, |ui| {
-                            sk.paint(ui);
+                            self.figure_exports
+                                .entry(format!("2D{i}"))
+                                .or_default()
+                                .show(sk, ui);
+                            sk.show(&mut self.sk_layers, ui);
                         });
                 });
             }
 
+            if *self.widget_stauses.get("2D all").unwrap_or(&false) {
+                egui::Window::new("2D all classes")
+                    .id(egui::Id::new("2D all"))
+                    .default_size(egui::vec2(400.0, 400.0))
+                    .min_width(250.0)
+                    .min_height(200.0)
+                    .show(ctx, |ui| {
+                        self.figure_exports
+                            .entry("2D all".to_owned())
+                            .or_default()
+                            .show(&self.projection, ui);
+                        self.projection.show(ui);
+                    });
+            }
+
+            if *self.widget_stauses.get("Histograms").unwrap_or(&false) {
+                self.histograms.iter().for_each(|histogram| {
+                    let i = histogram.class;
+                    egui::Window::new(format!("Histogram {i}"))
+                        .id(egui::Id::new(format!("Histogram{i}")))
+                        .default_size(egui::vec2(400.0, 250.0))
+                        .min_width(250.0)
+                        .min_height(200.0)
+                        .show(ctx, |ui| {
+                            self.figure_exports
+                                .entry(format!("Histogram{i}"))
+                                .or_default()
+                                .show(histogram, ui);
+                            histogram.show(ui);
+                        });
+                });
+            }
+
+            if *self.widget_stauses.get("Attributes").unwrap_or(&false) {
+                let mut changed = false;
+
+                egui::Window::new("Attributes")
+                    .default_size(egui::vec2(400.0, 400.0))
+                    .show(ctx, |ui| {
+                        changed = self
+                            .class_manager
+                            .binary_representations
+                            .attributes
+                            .show(&mut self.settings.excluded_attributes, ui);
+                    });
+
+                if changed {
+                    self.optimization_results = None;
+                    self.recalculate(ctx);
+                }
+            }
+
             if *self.widget_stauses.get("Information").unwrap_or(&false) {
                 egui::Window::new("Information")
                     .resizable(false)
                     .show(ctx, |ui| {
-                        frame.show(ui, |ui| self.add_stats(ui));
+                        frame.show(ui, |ui| {
+                            self.add_stats(ui);
+                            ui.separator();
+                            self.quality_table.show(ui);
+                            ui.separator();
+                            if self.data_export.show(ui) {
+                                self.data_export.export(&self.tables());
+                            }
+                        });
                     });
             }
 
@@ -141,6 +268,10 @@ impl eframe::App for MyApp {
                     .default_size(egui::vec2(400.0, 200.0))
                     .show(ctx, |ui| {
                         frame.show(ui, |ui| {
+                            self.figure_exports
+                                .entry("Allowances".to_owned())
+                                .or_default()
+                                .show(&self.corridor, ui);
                             self.corridor.show(ui);
                         });
                     });
@@ -153,12 +284,36 @@ impl eframe::App for MyApp {
                         egui::ScrollArea::new([true, true]).show(ui, |ui| {
                             frame.show(ui, |ui| {
                                 self.class_manager.show(ui);
+                                if let Some(operation) =
+                                    self.class_manager.show_operations("exam", ui)
+                                {
+                                    if let Err(err) = self.restructure(
+                                        class_loader::ClassType::Training,
+                                        operation,
+                                        ctx,
+                                    ) {
+                                        self.notifications.push(err);
+                                    }
+                                }
                                 self.class_manager.binary_representations.show(ui);
                             });
                         });
                     });
             }
 
+            if *self.widget_stauses.get("Matrices").unwrap_or(&false) {
+                egui::Window::new("Matrices")
+                    .default_size(egui::vec2(600.0, 500.0))
+                    .show(ctx, |ui| {
+                        self.matrix_view.show(
+                            &self.class_manager.classes,
+                            &self.class_manager.binary_representations,
+                            &self.corridor.allowances,
+                            ui,
+                        );
+                    });
+            }
+
             if *self.widget_stauses.get("Exam classes").unwrap_or(&false) {
                 egui::Window::new("Exam classes")
                     .resizable(false)
@@ -166,6 +321,17 @@ impl eframe::App for MyApp {
                         egui::ScrollArea::new([true, true]).show(ui, |ui| {
                             frame.show(ui, |ui| {
                                 self.exam_class_manager.show(ui);
+                                if let Some(operation) =
+                                    self.exam_class_manager.show_operations("training", ui)
+                                {
+                                    if let Err(err) = self.restructure(
+                                        class_loader::ClassType::Exam,
+                                        operation,
+                                        ctx,
+                                    ) {
+                                        self.notifications.push(err);
+                                    }
+                                }
                                 self.exam_class_manager.binary_representations.show(ui);
                             });
                         });
@@ -189,77 +355,263 @@ impl eframe::App for MyApp {
                 .get("Optimization results")
                 .unwrap_or(&false)
             {
-                if let Some(optimization_results) = &self.optimization_results {
+                let mut clicked_delta = None;
+
+                if let Some(optimization_results) = &mut self.optimization_results {
                     egui::Window::new(format!(
-                        "Optimization result of delta for class {}",
-                        self.class_manager.selected_class
+                        "Optimization result of delta by {}",
+                        optimization_results.objective
                     ))
+                    .id(egui::Id::new("Optimization results"))
                     .default_size(egui::vec2(250.0, 200.0))
                     .min_width(400.0)
                     .min_height(150.0)
                     .show(ctx, |ui| {
-                        optimization_results.show(ui);
+                        self.figure_exports
+                            .entry("Optimization results".to_owned())
+                            .or_default()
+                            .show(optimization_results, ui);
+                        clicked_delta = optimization_results.show(ui);
                     });
                 };
+
+                if let Some(delta) = clicked_delta {
+                    self.history.record(self.state());
+                    self.apply(Parameter::Delta, delta);
+                    self.recalculate(ctx);
+                }
+            }
+
+            if *self.widget_stauses.get("Base comparison").unwrap_or(&false) {
+                let mut chosen = None;
+
+                egui::Window::new("Base comparison")
+                    .default_size(egui::vec2(400.0, 200.0))
+                    .show(ctx, |ui| {
+                        if self.class_manager.classes.len() > 1 {
+                            ui.horizontal(|ui| {
+                                if ui.add(egui::Button::new("Compare")).clicked() {
+                                    self.compare_corridor_bases(ctx);
+                                }
+
+                                if ui.add(egui::Button::new("Auto base class")).clicked() {
+                                    self.auto_base_class(ctx);
+                                }
+                            });
+                        }
+
+                        if let Some(base_comparison) = &self.base_comparison {
+                            ui.separator();
+                            chosen = base_comparison.show(ui).cloned();
+                        }
+                    });
+
+                if let Some(candidate) = chosen {
+                    self.apply_base(&candidate, ctx);
+                }
+            }
+
+            if *self.widget_stauses.get("Optimizer").unwrap_or(&false) {
+                egui::Window::new("Optimizer")
+                    .default_size(egui::vec2(400.0, 300.0))
+                    .show(ctx, |ui| {
+                        if self.optimizer.show(self.class_loader.shape.attributes, ui) {
+                            if let Err(err) = self.optimize_parameters(ctx) {
+                                self.notifications.push(err);
+                            }
+                        }
+
+                        if let Some(optimization_log) = &self.optimization_log {
+                            ui.separator();
+                            self.figure_exports
+                                .entry("Optimizer".to_owned())
+                                .or_default()
+                                .show(optimization_log, ui);
+                            optimization_log.show(ui);
+                        }
+                    });
             }
         });
     }
 }
 
 impl MyApp {
+    fn add_class(&mut self, data: TextureData, ctx: &egui::Context) -> Result<(), Error> {
+        let state = self.state();
+        let corridor = self
+            .class_manager
+            .classes
+            .is_empty()
+            .then(|| Corridor::new(&data.bytes, data.shape()));
+        match self.class_loader.class_type {
+            class_loader::ClassType::Training => self.class_manager.add_class(data)?,
+            class_loader::ClassType::Exam => self.exam_class_manager.add_class(data)?,
+        }
+        self.history.record(state);
+        if let Some(corridor) = corridor {
+            self.corridor = corridor;
+        }
+
+        match self.class_loader.class_type {
+            class_loader::ClassType::Training => {
+                if self.class_manager.corridor_base == CorridorBase::AllClasses {
+                    self.set_base_class(self.class_manager.selected_class, ctx);
+                } else {
+                    self.recalculate(ctx);
+                }
+            }
+            class_loader::ClassType::Exam => {
+                self.exam_class_manager.recalculate_binary_representation(
+                    &self.corridor.allowances,
+                    self.settings.selection_level,
+                    &self.settings.excluded_attributes,
+                    ctx,
+                );
+                self.exam();
+            }
+        }
+
+        Ok(())
+    }
+
     fn exam(&mut self) {
+        // Training that failed left no criteria to examine with
+        if self.criterias.len()
+            != self
+                .class_manager
+                .binary_representations
+                .reference_vectors
+                .len()
+        {
+            self.exam_data = Vec::new();
+            return;
+        }
+
         self.exam_data = exam_data::exam(
             &self.class_manager.binary_representations.reference_vectors,
             &self.exam_class_manager.binary_representations.matrices,
             &self.criterias,
-            self.class_loader.size.1,
-        );
+            self.settings.radius_criterion,
+            self.class_loader.shape.attributes,
+        )
+        .unwrap_or_else(|err| {
+            self.notifications.push(err);
+            Vec::new()
+        });
     }
 
     fn calculate_criteria(&mut self) {
+        self.settings.set_classes(self.class_manager.classes.len());
+        let weights = self.settings.weights();
+
         self.criterias = (0..self.sk_manager.sk.len())
             .map(|i| {
                 Criteria::new(
                     i,
                     &self.sk_manager.distances_to_realizations[i],
-                    self.class_loader.size.1,
-                    self.sk_manager.sk[i].distance,
+                    self.sk_manager.distances[i][self.sk_manager.sk[i].closest],
+                    &weights,
+                    &self.settings.working_space,
                 )
             })
             .collect();
 
+        self.notifications.set_warnings(
+            self.class_manager
+                .identical()
+                .into_iter()
+                .map(|(first, second)| Warning::IdenticalClasses { first, second })
+                .chain(
+                    self.criterias
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, criteria)| !criteria.has_working_space())
+                        .map(|(class, _)| Warning::NoWorkingSpace { class }),
+                )
+                .collect(),
+        );
+
         self.sk_manager
             .sk
             .iter_mut()
             .enumerate()
             .for_each(|(i, sk)| {
                 sk.set_radius(
-                    self.criterias[i].r_kullback.to_vec(),
-                    self.criterias[i].r_shannon.to_vec(),
+                    self.criterias[i].radii(CriterionKind::Kullback).to_vec(),
+                    self.criterias[i].radii(CriterionKind::Shannon).to_vec(),
                 );
                 sk.set_radius_closest(
-                    self.criterias[sk.closest].r_kullback.to_vec(),
-                    self.criterias[sk.closest].r_shannon.to_vec(),
+                    self.criterias[sk.closest]
+                        .radii(CriterionKind::Kullback)
+                        .to_vec(),
+                    self.criterias[sk.closest]
+                        .radii(CriterionKind::Shannon)
+                        .to_vec(),
                 );
             });
+
+        self.quality_table.set_rows(
+            &self.criterias,
+            &self.sk_manager.sk,
+            self.settings.radius_criterion,
+        );
+
+        let radii: Vec<Option<f64>> = self
+            .criterias
+            .iter()
+            .map(|criteria| {
+                criteria
+                    .best_criteria(self.settings.radius_criterion)
+                    .map(|(radius, _)| radius as f64)
+            })
+            .collect();
+
+        self.histograms = radii
+            .iter()
+            .enumerate()
+            .map(|(i, &radius)| {
+                Histogram::new(i, &self.sk_manager.distances_to_realizations[i], radius)
+            })
+            .collect();
+
+        self.projection = Projection::new(
+            &self.sk_manager.distances,
+            &self.sk_manager.distances_to_realizations,
+            radii,
+        );
     }
 
     fn recalculate(&mut self, ctx: &egui::Context) {
-        self.class_manager
-            .recalculate_binary_representation(&self.corridor.allowances, ctx);
-        self.exam_class_manager
-            .recalculate_binary_representation(&self.corridor.allowances, ctx);
+        self.corridor.statistics(self.settings.statistics);
+        self.class_manager.recalculate_binary_representation(
+            &self.corridor.allowances,
+            self.settings.selection_level,
+            &self.settings.excluded_attributes,
+            ctx,
+        );
+        self.exam_class_manager.recalculate_binary_representation(
+            &self.corridor.allowances,
+            self.settings.selection_level,
+            &self.settings.excluded_attributes,
+            ctx,
+        );
 
         self.sk_manager = SKManager::new(
             &self.class_manager.binary_representations.matrices,
             &self.class_manager.binary_representations.reference_vectors,
-        );
+            self.class_loader.shape.attributes,
+            &self.settings.excluded_attributes,
+        )
+        .unwrap_or_else(|err| {
+            self.notifications.push(err);
+            SKManager::default()
+        });
 
         self.calculate_criteria();
         self.exam();
     }
 
-    fn set_base_class(&mut self, class: usize, ui: &mut egui::Ui) {
+    fn set_base_class(&mut self, class: usize, ctx: &egui::Context) {
         self.optimization_results = None;
         self.class_manager.selected_class = class;
 
@@ -267,25 +619,352 @@ impl MyApp {
             return;
         }
 
-        self.corridor.set_base_class(
-            &self.class_manager.classes[self.class_manager.selected_class].bytes,
-            self.class_manager.classes[0].texture.size().into(),
-        );
+        if let Some((bytes, shape)) = self.class_manager.base_realizations() {
+            self.corridor.set_base_class(&bytes, shape);
+        }
+
+        self.recalculate(ctx);
+    }
+
+    /// Applies the operation to training or exam classes, moving transferred classes
+    /// to the other manager, and recalculates.
+    ///
+    /// Fails without changes if the other manager does not accept a transferred class.
+    fn restructure(
+        &mut self,
+        class_type: class_loader::ClassType,
+        operation: ClassOperation,
+        ctx: &egui::Context,
+    ) -> Result<(), Error> {
+        let state = self.state();
+
+        let (manager, other) = match class_type {
+            class_loader::ClassType::Training => {
+                (&mut self.class_manager, &mut self.exam_class_manager)
+            }
+            class_loader::ClassType::Exam => {
+                (&mut self.exam_class_manager, &mut self.class_manager)
+            }
+        };
+        let transferred = match operation {
+            ClassOperation::Transfer(i) => manager.classes.get(i).cloned(),
+            _ => None,
+        };
+        if let Some(class) = transferred {
+            other.add_class(class)?;
+        }
+        let Some(sources) = manager.apply(operation, ctx) else {
+            return Ok(());
+        };
+        self.history.record(state);
+
+        if class_type == class_loader::ClassType::Training {
+            let weights = &self.settings.prior_weights;
+            self.settings.prior_weights = sources
+                .iter()
+                .map(|sources| {
+                    sources
+                        .iter()
+                        .map(|&class| weights.get(class).copied().unwrap_or(1.0))
+                        .sum::<f64>()
+                        / sources.len() as f64
+                })
+                .collect();
+        }
+        self.set_base_class(self.class_manager.selected_class, ctx);
 
-        self.recalculate(ui.ctx());
+        Ok(())
+    }
+
+    /// Sets a training parameter without recalculating.
+    fn apply(&mut self, parameter: Parameter, value: f64) {
+        let delta = value.max(0.0);
+
+        match parameter {
+            Parameter::Delta => {
+                self.delta = delta;
+                self.corridor.delta(self.delta);
+            }
+            Parameter::AttributeDelta(attribute) => {
+                self.corridor.attribute_delta(attribute, Some(delta))
+            }
+            Parameter::SelectionLevel => self.settings.selection_level = value,
+        }
+    }
+
+    /// Returns current values of the parameters.
+    fn parameter_values(&self, parameters: &[Parameter]) -> Vec<f64> {
+        parameters
+            .iter()
+            .map(|parameter| match parameter {
+                Parameter::Delta => self.delta,
+                Parameter::AttributeDelta(attribute) => self.corridor.delta_of(*attribute),
+                Parameter::SelectionLevel => self.settings.selection_level,
+            })
+            .collect()
+    }
+
+    /// Searches the parameter space with the strategy and applies the best point found.
+    fn run_optimizer(
+        &mut self,
+        space: &ParameterSpace,
+        strategy: Strategy,
+        objective: &dyn Objective,
+        ctx: &egui::Context,
+    ) -> OptimizationLog {
+        let parameters = space.parameters();
+        let start = self.parameter_values(&parameters);
+
+        let log = optimizer::optimize(space, strategy, objective, &start, |values| {
+            parameters
+                .iter()
+                .zip(values)
+                .for_each(|(&parameter, &value)| self.apply(parameter, value));
+            self.recalculate(ctx);
+
+            Evaluation::new(objective, &self.criterias)
+        });
+
+        let best = log.best().map_or(start, |best| best.values.clone());
+        parameters
+            .iter()
+            .zip(&best)
+            .for_each(|(&parameter, &value)| self.apply(parameter, value));
+        self.recalculate(ctx);
+
+        log
+    }
+
+    /// Returns the objective chosen in settings.
+    fn objective(&self) -> Box<dyn Objective> {
+        self.settings
+            .optimization_mode
+            .objective(self.settings.objective, self.class_manager.selected_class)
+    }
+
+    /// Fails if no point of the log gives a working space,
+    /// naming classes without one at the current parameters.
+    fn found(&self, log: &OptimizationLog) -> Result<(), Error> {
+        if log.best().is_some() {
+            return Ok(());
+        }
+
+        Err(Error::DegenerateCriteria {
+            classes: self
+                .criterias
+                .iter()
+                .enumerate()
+                .filter(|(_, criteria)| !criteria.has_working_space())
+                .map(|(class, _)| class)
+                .collect(),
+        })
+    }
+
+    /// Selects delta with the best value of the objective within working space.
+    fn optimize(&mut self, ctx: &egui::Context) -> Result<(), Error> {
+        if self.class_manager.classes.is_empty() {
+            return Err(Error::EmptyTrainingSet);
+        }
+
+        self.history.record(self.state());
+        let objective = self.objective();
+        let space = ParameterSpace::new(vec![self.settings.delta_range.clone()]);
+
+        let log = self.run_optimizer(&space, Strategy::Grid, objective.as_ref(), ctx);
+        let found = self.found(&log);
+
+        self.optimization_results = Some(OptimizationResults::new(log));
+
+        found
+    }
+
+    /// Optimizes delta by the objective with every candidate base of the corridor,
+    /// then restores the base and delta.
+    fn compare_bases(
+        &mut self,
+        candidates: Vec<(usize, CorridorBase)>,
+        objective: &dyn Objective,
+        ctx: &egui::Context,
+    ) -> BaseComparison {
+        let selected_class = self.class_manager.selected_class;
+        let corridor_base = self.class_manager.corridor_base.clone();
+        let delta = self.delta;
+        let space = ParameterSpace::new(vec![self.settings.delta_range.clone()]);
+
+        let candidates = candidates
+            .into_iter()
+            .map(|(class, base)| {
+                self.class_manager.corridor_base = base.clone();
+                self.set_base_class(class, ctx);
+                let log = self.run_optimizer(&space, Strategy::Grid, objective, ctx);
+                let best = log.best();
+
+                BaseCandidate {
+                    selected_class: class,
+                    base,
+                    classes: self.class_manager.base_classes(),
+                    delta: best.map(|best| best.values[0]),
+                    value: best.and_then(|best| best.evaluation.value),
+                }
+            })
+            .collect();
+
+        self.class_manager.corridor_base = corridor_base;
+        self.apply(Parameter::Delta, delta);
+        self.set_base_class(selected_class, ctx);
+
+        BaseComparison::new(objective.name(), objective.lower_is_better(), candidates)
+    }
+
+    /// Compares the selected class, all classes and the chosen classes as the corridor base.
+    fn compare_corridor_bases(&mut self, ctx: &egui::Context) {
+        let selected_class = self.class_manager.selected_class;
+        let mut candidates = vec![
+            (selected_class, CorridorBase::SelectedClass),
+            (selected_class, CorridorBase::AllClasses),
+        ];
+
+        if let CorridorBase::Subset(classes) = &self.class_manager.corridor_base {
+            candidates.push((selected_class, CorridorBase::Subset(classes.clone())));
+        }
+
+        let objective = self.objective();
+        self.base_comparison = Some(self.compare_bases(candidates, objective.as_ref(), ctx));
+    }
+
+    /// Optimizes delta with every class as the base by the criterion averaged over classes
+    /// and applies the best base.
+    fn auto_base_class(&mut self, ctx: &egui::Context) {
+        let candidates = (0..self.class_manager.classes.len())
+            .map(|class| (class, CorridorBase::SelectedClass))
+            .collect();
+        let objective = MeanCriterion(self.settings.objective);
+        let base_comparison = self.compare_bases(candidates, &objective, ctx);
+
+        if let Some(best) = base_comparison.best() {
+            self.apply_base(&base_comparison.candidates[best].clone(), ctx);
+        }
+
+        self.base_comparison = Some(base_comparison);
+    }
+
+    /// Applies the base and the best delta of a compared candidate.
+    fn apply_base(&mut self, candidate: &BaseCandidate, ctx: &egui::Context) {
+        self.history.record(self.state());
+        self.class_manager.corridor_base = candidate.base.clone();
+        if let Some(delta) = candidate.delta {
+            self.apply(Parameter::Delta, delta);
+        }
+        self.set_base_class(candidate.selected_class, ctx);
+    }
+
+    /// Runs the optimizer with the parameter space and strategy chosen in its window.
+    fn optimize_parameters(&mut self, ctx: &egui::Context) -> Result<(), Error> {
+        if self.class_manager.classes.is_empty() {
+            return Err(Error::EmptyTrainingSet);
+        }
+
+        let space = self.optimizer.space.clone();
+        let objective = self.objective();
+
+        self.history.record(self.state());
+        self.optimization_results = None;
+        let log = self.run_optimizer(&space, self.optimizer.strategy, objective.as_ref(), ctx);
+        let found = self.found(&log);
+        self.optimization_log = Some(log);
+
+        found
+    }
+
+    /// Returns the training state recorded by undo history.
+    fn state(&self) -> TrainingState {
+        TrainingState {
+            classes: self.class_manager.classes.clone(),
+            exam_classes: self.exam_class_manager.classes.clone(),
+            selected_class: self.class_manager.selected_class,
+            corridor_base: self.class_manager.corridor_base.clone(),
+            prior_weights: self.settings.prior_weights.clone(),
+            delta: self.delta,
+            attribute_deltas: self.corridor.attribute_deltas().to_vec(),
+        }
+    }
+
+    fn restore(&mut self, state: TrainingState, ctx: &egui::Context) {
+        self.class_manager.classes = state.classes;
+        self.exam_class_manager.classes = state.exam_classes;
+        self.class_manager.corridor_base = state.corridor_base;
+        self.settings.prior_weights = state.prior_weights;
+        self.delta = state.delta;
+        self.corridor.delta(self.delta);
+        self.corridor.clear_attribute_deltas();
+        state
+            .attribute_deltas
+            .iter()
+            .enumerate()
+            .filter(|(_, delta)| delta.is_some())
+            .for_each(|(attribute, &delta)| self.corridor.attribute_delta(attribute, delta));
+        self.set_base_class(state.selected_class, ctx);
+    }
+
+    fn undo(&mut self, ctx: &egui::Context) {
+        if let Some(state) = self.history.undo(self.state()) {
+            self.restore(state, ctx);
+        }
+    }
+
+    fn redo(&mut self, ctx: &egui::Context) {
+        if let Some(state) = self.history.redo(self.state()) {
+            self.restore(state, ctx);
+        }
     }
 
     fn add_controls(&mut self, ui: &mut egui::Ui) {
-        if self.class_manager.show_controls(ui).changed() {
-            self.set_base_class(self.class_manager.selected_class, ui);
+        // Controls change these in place, the rest of the state is recorded on change
+        let selected_class = self.class_manager.selected_class;
+        let corridor_base = self.class_manager.corridor_base.clone();
+        let delta = self.delta;
+
+        let response = self.class_manager.show_controls(ui);
+        if response.changed() {
+            self.history.record(TrainingState {
+                selected_class,
+                corridor_base,
+                ..self.state()
+            });
+            if let Some(class) = response.deleted() {
+                self.class_manager.delete(class);
+                self.settings.forget_class(class);
+            }
+            self.set_base_class(self.class_manager.selected_class, ui.ctx());
+        }
+
+        if self.class_manager.classes.len() > 1
+            && ui.add(egui::Button::new("Auto base class")).clicked()
+        {
+            self.auto_base_class(ui.ctx());
+            self.widget_stauses
+                .insert("Base comparison".to_owned(), true);
+        }
+
+        if self.settings.show(ui) {
+            self.optimization_results = None;
+            self.recalculate(ui.ctx());
         }
 
         ui.horizontal(|ui| {
             ui.add(egui::Label::new("Delta"));
-            if ui
-                .add(egui::Slider::new(&mut self.delta, u8::MIN..=u8::MAX))
-                .changed()
-            {
+            let slider = ui.add(egui::Slider::new(
+                &mut self.delta,
+                0.0..=self.settings.statistics.tolerance.max_delta(),
+            ));
+            // A drag is undone as a whole
+            if slider.drag_started() || (slider.changed() && !slider.dragged()) {
+                self.history.record(TrainingState {
+                    delta,
+                    ..self.state()
+                });
+            }
+            if slider.changed() {
                 self.optimization_results = None;
                 self.corridor.delta(self.delta);
                 self.recalculate(ui.ctx());
@@ -294,86 +973,106 @@ impl MyApp {
             if self.class_manager.classes.len() > 1
                 && ui.add(egui::Button::new("Optimize")).clicked()
             {
-                let results: Vec<(f64, f64, bool)> = (u8::MIN..u8::MAX)
-                    .map(|delta| {
-                        self.delta = delta;
-                        self.corridor.delta(self.delta);
-                        self.recalculate(ui.ctx());
-
-                        let max_shannon_criteria: Vec<Option<(usize, f64)>> = self
-                            .criterias
-                            .iter()
-                            .map(|c| c.max_shannon_criteria())
-                            .collect();
-
-                        let average_shannon = max_shannon_criteria
-                            .iter()
-                            .map(|c| c.map_or(0.0, |c| c.1))
-                            .sum::<f64>()
-                            / max_shannon_criteria.len() as f64;
-
-                        let max_kullback_criteria: Vec<Option<(usize, f64)>> = self
-                            .criterias
-                            .iter()
-                            .map(|c| c.max_kullback_criteria())
-                            .collect();
-
-                        let average_kullback = max_kullback_criteria
-                            .iter()
-                            .map(|c| c.map_or(0.0, |c| c.1))
-                            .sum::<f64>()
-                            / max_kullback_criteria.len() as f64;
-
-                        let in_working_space = max_shannon_criteria.iter().all(|c| {
-                            if let Some(c) = c {
-                                let characteristics = &self.criterias
-                                    [self.class_manager.selected_class]
-                                    .characteristics[c.0];
-
-                                characteristics.d1 >= 0.5
-                                    && characteristics.d2 >= 0.5
-                                    && characteristics.d1 <= 1.0
-                                    && characteristics.d2 <= 1.0
-                            } else {
-                                false
-                            }
-                        });
+                if let Err(err) = self.optimize(ui.ctx()) {
+                    self.notifications.push(err);
+                }
+            }
 
-                        (average_shannon, average_kullback, in_working_space)
-                    })
-                    .collect();
-
-                let best_delta = results
-                    .iter()
-                    .enumerate()
-                    .filter(|(_, (_, _, in_working_space))| *in_working_space)
-                    .max_by(|(_, (a_shannon, _, _)), (_, (b_shannon, _, _))| {
-                        a_shannon.total_cmp(b_shannon)
-                    })
-                    .map(|(i, _)| i as u8)
-                    .unwrap_or(0);
-
-                self.delta = best_delta;
-                self.corridor.delta(self.delta);
+            if self.corridor.has_attribute_deltas()
+                && ui
+                    .add(egui::Button::new("Reset attribute deltas"))
+                    .clicked()
+            {
+                self.history.record(self.state());
+                self.corridor.clear_attribute_deltas();
                 self.recalculate(ui.ctx());
-
-                self.optimization_results = Some(OptimizationResults::from(results));
             }
         });
 
-        if self.exam_class_manager.show_controls(ui).changed() {
+        let response = self.exam_class_manager.show_controls(ui);
+        if let Some(class) = response.deleted() {
+            self.history.record(self.state());
+            self.exam_class_manager.delete(class);
+            self.exam_class_manager.recalculate_binary_representation(
+                &self.corridor.allowances,
+                self.settings.selection_level,
+                &self.settings.excluded_attributes,
+                ui.ctx(),
+            );
+        }
+        if response.changed() {
             self.exam();
         }
     }
 
+    /// Collects every computed result as tables.
+    fn tables(&self) -> Vec<Table> {
+        let mut tables = self.settings.tables();
+
+        tables.extend(self.corridor.tables());
+
+        tables.extend(self.class_manager.binary_representations.tables());
+        tables.extend(
+            self.exam_class_manager
+                .binary_representations
+                .tables()
+                .into_iter()
+                .map(|mut table| {
+                    table.name = format!("exam_{}", table.name);
+                    table
+                }),
+        );
+        tables.extend(
+            self.class_manager
+                .binary_representations
+                .attributes
+                .tables(),
+        );
+        tables.extend(self.sk_manager.tables());
+        tables.extend(self.quality_table.tables());
+        tables.extend(self.projection.tables());
+        tables.extend(self.histograms.iter().flat_map(|histogram| {
+            histogram.tables().into_iter().map(|mut table| {
+                table.name = format!("{}_{}", table.name, histogram.class);
+                table
+            })
+        }));
+        tables.extend(self.criterias.iter().enumerate().flat_map(|(i, criteria)| {
+            criteria.tables().into_iter().map(move |mut table| {
+                table.name = format!("{}_{i}", table.name);
+                table
+            })
+        }));
+
+        if let Some(optimization_results) = &self.optimization_results {
+            tables.extend(optimization_results.tables());
+        }
+
+        if let Some(optimization_log) = &self.optimization_log {
+            tables.extend(optimization_log.tables());
+        }
+
+        if let Some(base_comparison) = &self.base_comparison {
+            tables.extend(base_comparison.tables());
+        }
+
+        tables
+    }
+
     fn add_stats(&self, ui: &mut egui::Ui) {
+        let realizations: Vec<String> = self
+            .class_manager
+            .classes
+            .iter()
+            .map(|class| class.shape().realizations.to_string())
+            .collect();
         ui.add(egui::Label::new(format!(
             "Number of realizations: {}",
-            self.class_loader.size.0
+            realizations.join(", ")
         )));
         ui.add(egui::Label::new(format!(
             "Number of attributes: {}",
-            self.class_loader.size.1
+            self.class_loader.shape.attributes
         )));
         ui.add(egui::Label::new(format!(
             "Number of classes: {}",
@@ -387,15 +1086,38 @@ impl MyApp {
 
     fn add_widgets_controls(&mut self, ui: &mut egui::Ui) {
         ui.horizontal(|ui| {
+            let ctx = ui.ctx().clone();
+            if ui
+                .add_enabled(self.history.can_undo(), egui::Button::new("Undo"))
+                .on_hover_text(ctx.format_shortcut(&history::UNDO))
+                .clicked()
+            {
+                self.undo(&ctx);
+            }
+            if ui
+                .add_enabled(self.history.can_redo(), egui::Button::new("Redo"))
+                .on_hover_text(ctx.format_shortcut(&history::REDO))
+                .clicked()
+            {
+                self.redo(&ctx);
+            }
+            ui.separator();
+
             self.add_button("Information", ui);
             self.add_button("Settings", ui);
             self.add_button("Classes", ui);
+            self.add_button("Matrices", ui);
             self.add_button("Allowances", ui);
             self.add_button("2D", ui);
+            self.add_button("2D all", ui);
+            self.add_button("Histograms", ui);
+            self.add_button("Attributes", ui);
             self.add_button("Criteria", ui);
             self.add_button("Exam classes", ui);
             self.add_button("Exam results", ui);
             self.add_button("Optimization results", ui);
+            self.add_button("Optimizer", ui);
+            self.add_button("Base comparison", ui);
         });
     }
 
@@ -0,0 +1,201 @@
+use eframe::egui::{vec2, Color32, ComboBox, Label, Rect, Sense, Stroke, TextureId, Ui};
+use egui_plot::{Plot, PlotImage, PlotPoint};
+
+use crate::{
+    binary_representation::BinaryRepresentation, class_data::TextureData, corridor::Allowances,
+    shape::Shape,
+};
+
+const DIFFERENT: Color32 = Color32::RED;
+
+/// Enlarged binary matrix of a class and bit-by-bit comparison of two reference vectors.
+#[derive(Debug, Default)]
+pub struct MatrixView {
+    /// Class of the shown binary matrix.
+    pub class: usize,
+    /// Classes of the compared reference vectors.
+    pub compared: [usize; 2],
+}
+
+impl MatrixView {
+    pub fn show(
+        &mut self,
+        classes: &[TextureData],
+        representation: &BinaryRepresentation,
+        allowances: &Allowances,
+        ui: &mut Ui,
+    ) {
+        let count = classes.len().min(representation.matrices.len());
+        if count == 0 {
+            ui.label("Nothing to show");
+            return;
+        }
+
+        self.class = self.class.min(count - 1);
+        self.compared = self.compared.map(|class| class.min(count - 1));
+
+        ui.add(Label::new("Reference vectors"));
+        ui.horizontal(|ui| {
+            class_combo_box("First vector", &mut self.compared[0], count, ui);
+            class_combo_box("Second vector", &mut self.compared[1], count, ui);
+        });
+        let [first, second] = self
+            .compared
+            .map(|class| &representation.reference_vectors[class].bytes);
+        show_diff(first, second, self.compared, ui);
+
+        ui.separator();
+
+        ui.horizontal(|ui| {
+            ui.add(Label::new("Binary matrix of"));
+            class_combo_box("Matrix", &mut self.class, count, ui);
+        });
+        show_matrix(
+            &representation.matrices[self.class],
+            &classes[self.class].bytes,
+            allowances,
+            ui,
+        );
+    }
+}
+
+fn class_combo_box(id: &str, class: &mut usize, count: usize, ui: &mut Ui) {
+    ComboBox::from_id_source(id)
+        .selected_text(format!("Class {class}"))
+        .show_ui(ui, |ui| {
+            (0..count).for_each(|i| {
+                ui.selectable_value(class, i, format!("Class {i}"));
+            });
+        });
+}
+
+/// Attributes with different bits in two reference vectors.
+pub fn differing(first: &[u8], second: &[u8]) -> Vec<usize> {
+    first
+        .iter()
+        .zip(second)
+        .enumerate()
+        .filter(|(_, (a, b))| a != b)
+        .map(|(i, _)| i)
+        .collect()
+}
+
+/// Draws both vectors one cell per attribute, differing attributes in red.
+fn show_diff(first: &[u8], second: &[u8], classes: [usize; 2], ui: &mut Ui) {
+    let differing = differing(first, second);
+    let attributes = first.len().max(1);
+    let width = ui.available_width();
+    let cell = width / attributes as f32;
+
+    let (rect, response) = ui.allocate_exact_size(vec2(width, 40.0), Sense::hover());
+    let painter = ui.painter_at(rect);
+
+    [first, second]
+        .iter()
+        .enumerate()
+        .for_each(|(row, vector)| {
+            vector.iter().enumerate().for_each(|(attribute, &bit)| {
+                let min = rect.min + vec2(attribute as f32 * cell, row as f32 * 20.0);
+                let color = if differing.binary_search(&attribute).is_ok() {
+                    DIFFERENT
+                } else {
+                    Color32::from_gray(bit)
+                };
+
+                painter.rect_filled(Rect::from_min_size(min, vec2(cell, 18.0)), 0.0, color);
+            });
+        });
+    painter.rect_stroke(rect, 0.0, Stroke::new(1.0, Color32::GRAY));
+
+    let bit = |byte: u8| (byte == u8::MAX) as u8;
+    response.on_hover_ui_at_pointer(|ui| {
+        let Some(pointer) = ui.ctx().pointer_hover_pos() else {
+            return;
+        };
+        let attribute = ((pointer.x - rect.min.x) / cell) as usize;
+        if attribute < first.len() {
+            ui.label(format!(
+                "Attribute {attribute}\nClass {}: {}\nClass {}: {}",
+                classes[0],
+                bit(first[attribute]),
+                classes[1],
+                bit(second[attribute])
+            ));
+        }
+    });
+
+    let attributes: Vec<String> = differing.iter().map(usize::to_string).collect();
+    ui.add(Label::new(format!(
+        "{} differing attributes: {}",
+        differing.len(),
+        attributes.join(", ")
+    )));
+}
+
+/// Cell of a matrix of `shape` under a point of the plot,
+/// where attributes go right from 0 and realizations go down from 0.
+pub fn cell(point: PlotPoint, shape: Shape) -> Option<(usize, usize)> {
+    let (attribute, realization) = (point.x.floor(), (-point.y).floor());
+
+    (attribute >= 0.0
+        && realization >= 0.0
+        && (attribute as usize) < shape.attributes
+        && (realization as usize) < shape.realizations)
+        .then_some((attribute as usize, realization as usize))
+}
+
+/// Shows a zoomable binary matrix, hovering a cell shows the original brightness and allowances.
+fn show_matrix(matrix: &TextureData, bytes: &[u8], allowances: &Allowances, ui: &mut Ui) {
+    let shape = matrix.shape();
+    let [width, height] = shape.image_size().map(|size| size as f32);
+    let texture: TextureId = matrix.texture.id();
+
+    let response = Plot::new(ui.id().with("Matrix"))
+        .data_aspect(1.0)
+        .show_grid(false)
+        .x_axis_label("Attribute")
+        .y_axis_label("Realization")
+        .y_axis_formatter(|y, _, _| format!("{}", -y))
+        .show(ui, |ui| {
+            ui.image(PlotImage::new(
+                texture,
+                PlotPoint::new(width / 2.0, -height / 2.0),
+                vec2(width, height),
+            ));
+
+            ui.pointer_coordinate()
+        });
+
+    let Some((attribute, realization)) = response.inner.and_then(|point| cell(point, shape)) else {
+        return;
+    };
+
+    let index = attribute + realization * shape.attributes;
+    let value = bytes[index];
+    response.response.on_hover_text_at_pointer(format!(
+        "Realization {realization}, attribute {attribute}\nBrightness {value}\nAllowances {:.2} to {:.2}\nBit {}",
+        allowances.lower[attribute],
+        allowances.upper[attribute],
+        (matrix.bytes[index] == u8::MAX) as u8,
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn differing_attributes_are_found() {
+        assert_eq!(differing(&[0, 255, 255, 0], &[0, 0, 255, 255]), [1, 3]);
+    }
+
+    #[test]
+    fn hovered_point_is_matched_to_cell() {
+        let shape = Shape::new(3, 2);
+
+        assert_eq!(cell(PlotPoint::new(2.5, -0.2), shape), Some((2, 0)));
+        assert_eq!(cell(PlotPoint::new(0.1, -1.9), shape), Some((0, 1)));
+        assert_eq!(cell(PlotPoint::new(3.1, -0.5), shape), None);
+        assert_eq!(cell(PlotPoint::new(1.0, 0.5), shape), None);
+    }
+}
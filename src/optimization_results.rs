@@ -1,61 +1,366 @@
-use crate::draw::Show;
-use eframe::egui::Ui;
-use egui_plot::{Legend, Line, Plot, PlotPoints};
+use crate::criterion::CriterionKind;
+use crate::figure::{Figure, FigureLine, Panel, ToFigure, PALETTE};
+use crate::optimizer::OptimizationLog;
+use crate::table::{Table, ToTables};
+use eframe::egui::{DragValue, Label, Ui};
+use eframe::epaint::Color32;
+use egui_plot::{Legend, Line, MarkerShape, Plot, PlotPoints, Points, VLine};
 
 pub struct OptimizationResults {
-    pub kullback_criteria: Vec<f64>,
-    pub shannon_criteria: Vec<f64>,
-    pub working_space: Vec<bool>,
+    /// Name of the optimized objective.
+    pub objective: String,
+    lower_is_better: bool,
+    pub deltas: Vec<f64>,
+    /// Value of the objective for every delta, `None` outside working space,
+    /// so the plotted objective also shows the working space.
+    pub values: Vec<Option<f64>>,
+    /// Values of every criterion in the order of [`CriterionKind::ALL`] for every delta.
+    pub criteria: Vec<Vec<Option<f64>>>,
+    /// Best value of the criterion of the objective for every class and delta.
+    pub classes: Vec<Vec<Option<f64>>>,
+    /// Index of the delta with the best value of the objective.
+    pub best: Option<usize>,
+    /// Largest difference from the best value, relative to it, of near-optimal deltas.
+    pub tolerance: f64,
 }
 
-impl Show for OptimizationResults {
-    fn show(&self, ui: &mut Ui) {
-        Plot::new("Optimization results")
+impl OptimizationResults {
+    /// Collects evaluations of a search over delta.
+    pub fn new(log: OptimizationLog) -> Self {
+        let best = log
+            .best()
+            .and_then(|best| log.entries.iter().position(|entry| entry == best));
+        let classes = log
+            .entries
+            .first()
+            .map_or(0, |entry| entry.evaluation.classes.len());
+
+        Self {
+            criteria: CriterionKind::ALL
+                .iter()
+                .map(|kind| {
+                    log.entries
+                        .iter()
+                        .map(|entry| entry.evaluation.criteria[kind.index()])
+                        .collect()
+                })
+                .collect(),
+            classes: (0..classes)
+                .map(|class| {
+                    log.entries
+                        .iter()
+                        .map(|entry| entry.evaluation.classes[class])
+                        .collect()
+                })
+                .collect(),
+            deltas: log.entries.iter().map(|entry| entry.values[0]).collect(),
+            values: log
+                .entries
+                .iter()
+                .map(|entry| entry.evaluation.value)
+                .collect(),
+            objective: log.objective,
+            lower_is_better: log.lower_is_better,
+            best,
+            tolerance: 0.01,
+        }
+    }
+
+    pub fn best_delta(&self) -> Option<f64> {
+        self.best.map(|best| self.deltas[best])
+    }
+
+    /// Indices of deltas with values within tolerance of the best value, including the best one.
+    pub fn near_optimal(&self) -> Vec<usize> {
+        let Some(best) = self.best.and_then(|best| self.values[best]) else {
+            return Vec::new();
+        };
+        let tolerance = self.tolerance * best.abs().max(f64::EPSILON);
+
+        self.values
+            .iter()
+            .enumerate()
+            .filter(|(_, value)| value.is_some_and(|value| (value - best).abs() <= tolerance))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Class with the worst value of the criterion at the best delta.
+    pub fn limiting_class(&self) -> Option<usize> {
+        let best = self.best?;
+
+        self.classes
+            .iter()
+            .enumerate()
+            .filter_map(|(class, values)| Some((class, values[best]?)))
+            .min_by(|(_, a), (_, b)| {
+                if self.lower_is_better {
+                    b.total_cmp(a)
+                } else {
+                    a.total_cmp(b)
+                }
+            })
+            .map(|(class, _)| class)
+    }
+
+    fn objective_points(&self) -> Vec<[f64; 2]> {
+        points(&self.deltas, &self.values)
+    }
+
+    fn near_optimal_points(&self) -> Vec<[f64; 2]> {
+        self.near_optimal()
+            .into_iter()
+            .filter_map(|i| Some([self.deltas[i], self.values[i]?]))
+            .collect()
+    }
+
+    fn summary(&self) -> String {
+        let Some(best) = self.best else {
+            return "No delta has working space".to_owned();
+        };
+
+        let mut summary = format!(
+            "Best delta {}: {:.4}",
+            self.deltas[best],
+            self.values[best].unwrap_or_default()
+        );
+
+        if let Some(class) = self.limiting_class() {
+            summary += &format!(", limited by class {class}");
+        }
+
+        summary
+    }
+
+    /// Shows the results and returns the delta clicked on the plot.
+    pub fn show(&mut self, ui: &mut Ui) -> Option<f64> {
+        ui.add(Label::new(format!("Objective: {}", self.objective)));
+        ui.add(Label::new(self.summary()));
+
+        ui.horizontal(|ui| {
+            ui.add(Label::new("Near optimum within"));
+            ui.add(
+                DragValue::new(&mut self.tolerance)
+                    .speed(0.001)
+                    .clamp_range(0.0..=1.0)
+                    .custom_formatter(|value, _| format!("{:.1}%", value * 100.0))
+                    .custom_parser(|text| {
+                        text.trim_end_matches('%')
+                            .parse::<f64>()
+                            .ok()
+                            .map(|value| value / 100.0)
+                    }),
+            );
+
+            let deltas: Vec<String> = self
+                .near_optimal()
+                .iter()
+                .map(|&i| self.deltas[i].to_string())
+                .collect();
+            ui.add(Label::new(deltas.join(", ")));
+        });
+
+        let response = Plot::new("Optimization results")
             .legend(Legend::default())
             .auto_bounds_x()
             .auto_bounds_y()
             .show(ui, |ui| {
-                ui.line(
-                    Line::new(
-                        self.kullback_criteria
-                            .iter()
-                            .enumerate()
-                            .filter(|(_, &y)| y != 0.0)
-                            .map(|(i, &y)| [i as f64, y])
-                            .collect::<Vec<[f64; 2]>>(),
-                    )
-                    .name("Kullback"),
-                );
-                ui.line(
-                    Line::new(
-                        self.shannon_criteria
-                            .iter()
-                            .enumerate()
-                            .filter(|(_, &y)| y != 0.0)
-                            .map(|(i, &y)| [i as f64, y])
-                            .collect::<Vec<[f64; 2]>>(),
-                    )
-                    .name("Shannon"),
+                segments(self.objective_points())
+                    .into_iter()
+                    .for_each(|segment| {
+                        ui.line(
+                            Line::new(PlotPoints::from(segment))
+                                .name(&self.objective)
+                                .color(PALETTE[0])
+                                .fill(0.0),
+                        );
+                    });
+
+                self.classes.iter().enumerate().for_each(|(class, values)| {
+                    let color = PALETTE[(class + 1) % PALETTE.len()];
+
+                    segments(points(&self.deltas, values))
+                        .into_iter()
+                        .for_each(|segment| {
+                            ui.line(
+                                Line::new(PlotPoints::from(segment))
+                                    .name(format!("Class {class}"))
+                                    .color(color),
+                            );
+                        });
+                });
+
+                ui.points(
+                    Points::new(self.near_optimal_points())
+                        .name("Near optimum")
+                        .shape(MarkerShape::Circle)
+                        .radius(3.0)
+                        .color(Color32::GRAY),
                 );
 
-                let points: PlotPoints = self
-                    .working_space
-                    .iter()
-                    .enumerate()
-                    .filter(|(_, &w)| w)
-                    .map(|(i, _)| [i as f64, self.shannon_criteria[i]])
-                    .collect();
-                ui.line(Line::new(points).name("Working space").fill(0.0));
+                if let Some(delta) = self.best_delta() {
+                    ui.vline(VLine::new(delta).name("Optimum").color(Color32::RED));
+                }
+
+                ui.pointer_coordinate()
             });
+
+        if !response.response.clicked() {
+            return None;
+        }
+
+        let x = response.inner?.x;
+
+        self.deltas
+            .iter()
+            .copied()
+            .min_by(|a, b| (a - x).abs().total_cmp(&(b - x).abs()))
     }
 }
 
-impl From<Vec<(f64, f64, bool)>> for OptimizationResults {
-    fn from(value: Vec<(f64, f64, bool)>) -> Self {
-        Self {
-            shannon_criteria: value.iter().map(|(s, _, _)| *s).collect(),
-            kullback_criteria: value.iter().map(|(_, k, _)| *k).collect(),
-            working_space: value.iter().map(|(_, _, w)| *w).collect(),
+/// Points of values at deltas, with NaN where a value is missing to split the line.
+fn points(deltas: &[f64], values: &[Option<f64>]) -> Vec<[f64; 2]> {
+    deltas
+        .iter()
+        .zip(values)
+        .map(|(&delta, value)| [delta, value.unwrap_or(f64::NAN)])
+        .collect()
+}
+
+/// Runs of points without missing values.
+fn segments(points: Vec<[f64; 2]>) -> Vec<Vec<[f64; 2]>> {
+    points
+        .split(|p| !p[1].is_finite())
+        .filter(|segment| !segment.is_empty())
+        .map(<[[f64; 2]]>::to_vec)
+        .collect()
+}
+
+impl ToFigure for OptimizationResults {
+    fn figure(&self) -> Figure {
+        let mut panel = Panel::new(self.objective.clone())
+            .line(FigureLine::new(self.objective.clone(), self.objective_points()).fill(0.0));
+
+        for (class, values) in self.classes.iter().enumerate() {
+            let mut line = FigureLine::new(format!("Class {class}"), points(&self.deltas, values));
+            line.color = Some(PALETTE[(class + 1) % PALETTE.len()]);
+            panel = panel.line(line);
+        }
+
+        if let Some(best) = self.best {
+            let delta = self.deltas[best];
+            let mut optimum = FigureLine::new(
+                "Optimum",
+                vec![[delta, 0.0], [delta, self.values[best].unwrap_or_default()]],
+            );
+            optimum.color = Some(Color32::RED);
+            let step = self
+                .deltas
+                .windows(2)
+                .map(|pair| (pair[1] - pair[0]).abs())
+                .filter(|step| *step > 0.0)
+                .fold(f64::INFINITY, f64::min);
+            let half_step = if step.is_finite() { step / 2.0 } else { 0.5 };
+            let near_optimal = self
+                .near_optimal()
+                .iter()
+                .map(|&i| [self.deltas[i] - half_step, self.deltas[i] + half_step])
+                .collect();
+
+            panel = panel
+                .line(optimum)
+                .region("Near optimum", Color32::GRAY, near_optimal);
         }
+
+        Figure::new(vec![panel])
+    }
+}
+
+impl ToTables for OptimizationResults {
+    fn tables(&self) -> Vec<Table> {
+        let criteria: Vec<String> = CriterionKind::ALL
+            .iter()
+            .map(|kind| kind.column())
+            .collect();
+        let classes: Vec<String> = (0..self.classes.len())
+            .map(|class| format!("class_{class}"))
+            .collect();
+        let mut columns = vec!["delta", "objective"];
+        columns.extend(criteria.iter().map(String::as_str));
+        columns.extend(classes.iter().map(String::as_str));
+        columns.extend(["working_space", "optimum", "near_optimal"]);
+
+        let near_optimal = self.near_optimal();
+        let mut table = Table::new("optimization", &columns);
+
+        self.values.iter().enumerate().for_each(|(i, value)| {
+            let mut row = vec![self.deltas[i].into(), value.unwrap_or(f64::NAN).into()];
+            row.extend(self.criteria.iter().map(|criteria| criteria[i].into()));
+            row.extend(
+                self.classes
+                    .iter()
+                    .map(|values| values[i].unwrap_or(f64::NAN).into()),
+            );
+            row.push(value.is_some().into());
+            row.push((self.best == Some(i)).into());
+            row.push(near_optimal.contains(&i).into());
+
+            table.push(row);
+        });
+
+        vec![table]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimizer::{Evaluation, LogEntry, Parameter, Strategy};
+
+    fn results(values: &[Option<f64>], classes: &[[Option<f64>; 2]]) -> OptimizationResults {
+        OptimizationResults::new(OptimizationLog {
+            objective: "Mean Shannon".to_owned(),
+            strategy: Strategy::Grid,
+            parameters: vec![Parameter::Delta],
+            entries: values
+                .iter()
+                .zip(classes)
+                .enumerate()
+                .map(|(delta, (&value, classes))| LogEntry {
+                    values: vec![delta as f64],
+                    evaluation: Evaluation {
+                        value,
+                        criteria: vec![Some(0.0); CriterionKind::ALL.len()],
+                        classes: classes.to_vec(),
+                    },
+                })
+                .collect(),
+            lower_is_better: false,
+        })
+    }
+
+    #[test]
+    fn best_delta_and_limiting_class_are_found() {
+        let results = results(
+            &[None, Some(0.0), Some(0.5), Some(0.499)],
+            &[
+                [None, None],
+                [Some(0.0), Some(0.0)],
+                [Some(0.7), Some(0.3)],
+                [Some(0.5), Some(0.498)],
+            ],
+        );
+
+        assert_eq!(results.best_delta(), Some(2.0));
+        assert_eq!(results.limiting_class(), Some(1));
+        assert_eq!(results.near_optimal(), [2, 3]);
+    }
+
+    #[test]
+    fn zero_values_are_kept() {
+        let results = results(&[Some(0.0), None], &[[Some(0.0); 2], [None; 2]]);
+
+        assert_eq!(results.objective_points()[0], [0.0, 0.0]);
+        assert_eq!(results.best, Some(0));
     }
 }
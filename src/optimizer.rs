@@ -0,0 +1,1056 @@
+use std::{collections::HashMap, fmt::Display};
+
+use eframe::egui::{Button, ComboBox, DragValue, Label, Ui};
+use egui_plot::{Legend, Line, Plot};
+
+use crate::{
+    criteria::Criteria,
+    criterion::CriterionKind,
+    draw::Show,
+    error::Error,
+    figure::{Figure, FigureLine, Panel, ToFigure},
+    table::{Table, ToTables},
+};
+
+/// Parameter of training that can be optimized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Parameter {
+    /// Common delta of all attributes.
+    Delta,
+    /// Delta of one attribute, overriding the common delta.
+    AttributeDelta(usize),
+    /// Share of realizations above which a bit of a reference vector is set.
+    SelectionLevel,
+}
+
+impl std::str::FromStr for Parameter {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "delta" => Ok(Parameter::Delta),
+            "selection_level" => Ok(Parameter::SelectionLevel),
+            _ => value
+                .strip_prefix("delta_")
+                .and_then(|attribute| attribute.parse().ok())
+                .map(Parameter::AttributeDelta)
+                .ok_or_else(|| Error::InvalidArgument(format!("Unknown parameter {value}"))),
+        }
+    }
+}
+
+impl Display for Parameter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Parameter::Delta => write!(f, "delta"),
+            Parameter::AttributeDelta(attribute) => write!(f, "delta_{attribute}"),
+            Parameter::SelectionLevel => write!(f, "selection_level"),
+        }
+    }
+}
+
+/// Values of a parameter from `min` to `max` with a step.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParameterRange {
+    pub parameter: Parameter,
+    pub min: f64,
+    pub max: f64,
+    pub step: f64,
+}
+
+impl ParameterRange {
+    pub fn new(parameter: Parameter, min: f64, max: f64, step: f64) -> Self {
+        Self {
+            parameter,
+            min,
+            max,
+            step,
+        }
+    }
+
+    /// Default range of values searched for the parameter.
+    pub fn of(parameter: Parameter) -> Self {
+        match parameter {
+            Parameter::Delta | Parameter::AttributeDelta(_) => {
+                Self::new(parameter, 0.0, u8::MAX as f64, 1.0)
+            }
+            Parameter::SelectionLevel => Self::new(parameter, 0.05, 0.95, 0.05),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        if self.step <= 0.0 || self.max < self.min {
+            1
+        } else {
+            ((self.max - self.min) / self.step + 1e-9).floor() as usize + 1
+        }
+    }
+
+    pub fn value(&self, index: usize) -> f64 {
+        (self.min + index as f64 * self.step).min(self.max)
+    }
+
+    /// Index of the value closest to `value`.
+    pub fn index_of(&self, value: f64) -> usize {
+        if self.step <= 0.0 {
+            return 0;
+        }
+
+        (((value - self.min) / self.step).round().max(0.0) as usize).min(self.len() - 1)
+    }
+}
+
+/// Values of training parameters to search.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ParameterSpace {
+    pub ranges: Vec<ParameterRange>,
+}
+
+impl ParameterSpace {
+    pub fn new(ranges: Vec<ParameterRange>) -> Self {
+        Self { ranges }
+    }
+
+    pub fn parameters(&self) -> Vec<Parameter> {
+        self.ranges.iter().map(|range| range.parameter).collect()
+    }
+
+    /// Number of points of the whole grid.
+    pub fn size(&self) -> usize {
+        self.ranges
+            .iter()
+            .map(ParameterRange::len)
+            .fold(1usize, usize::saturating_mul)
+    }
+
+    fn values(&self, indices: &[usize]) -> Vec<f64> {
+        self.ranges
+            .iter()
+            .zip(indices)
+            .map(|(range, &index)| range.value(index))
+            .collect()
+    }
+
+    fn indices(&self, values: &[f64]) -> Vec<usize> {
+        self.ranges
+            .iter()
+            .enumerate()
+            .map(|(i, range)| {
+                values
+                    .get(i)
+                    .map_or(range.len() / 2, |&value| range.index_of(value))
+            })
+            .collect()
+    }
+}
+
+/// Function of trained criteria maximized or minimized by optimization.
+pub trait Objective {
+    fn name(&self) -> String;
+
+    /// Criterion of classes the objective is calculated from.
+    fn criterion(&self) -> CriterionKind;
+
+    /// Returns the value of the objective, `None` if training has no working space.
+    fn value(&self, criterias: &[Criteria]) -> Option<f64>;
+
+    fn lower_is_better(&self) -> bool {
+        self.criterion().criterion().lower_is_better()
+    }
+}
+
+/// Best value of a criterion averaged over classes.
+pub struct MeanCriterion(pub CriterionKind);
+
+impl Objective for MeanCriterion {
+    fn name(&self) -> String {
+        format!("Mean {}", self.0)
+    }
+
+    fn criterion(&self) -> CriterionKind {
+        self.0
+    }
+
+    fn value(&self, criterias: &[Criteria]) -> Option<f64> {
+        let values = criterias
+            .iter()
+            .map(|criteria| criteria.best_criteria(self.0).map(|(_, value)| value))
+            .collect::<Option<Vec<f64>>>()?;
+
+        (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+    }
+}
+
+/// Best value of a criterion for one class against its closest neighbour.
+pub struct ClassCriterion {
+    pub kind: CriterionKind,
+    pub class: usize,
+}
+
+impl Objective for ClassCriterion {
+    fn name(&self) -> String {
+        format!("{} of class {}", self.kind, self.class)
+    }
+
+    fn criterion(&self) -> CriterionKind {
+        self.kind
+    }
+
+    fn value(&self, criterias: &[Criteria]) -> Option<f64> {
+        criterias
+            .get(self.class)?
+            .best_criteria(self.kind)
+            .map(|(_, value)| value)
+    }
+}
+
+/// Best value of a criterion for the class where it is the worst.
+pub struct WorstClassCriterion(pub CriterionKind);
+
+impl Objective for WorstClassCriterion {
+    fn name(&self) -> String {
+        format!("Worst-class {}", self.0)
+    }
+
+    fn criterion(&self) -> CriterionKind {
+        self.0
+    }
+
+    fn value(&self, criterias: &[Criteria]) -> Option<f64> {
+        let lower_is_better = self.lower_is_better();
+
+        criterias
+            .iter()
+            .map(|criteria| criteria.best_criteria(self.0).map(|(_, value)| value))
+            .collect::<Option<Vec<f64>>>()?
+            .into_iter()
+            .reduce(|a, b| if lower_is_better { a.max(b) } else { a.min(b) })
+    }
+}
+
+/// Which classes the optimized criterion is taken from.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum OptimizationMode {
+    /// The selected class against its closest neighbour.
+    SelectedClass,
+    /// Average over all classes.
+    #[default]
+    Average,
+    /// The class with the worst value, maximizing the minimum.
+    Maximin,
+}
+
+impl OptimizationMode {
+    pub const ALL: [OptimizationMode; 3] = [
+        OptimizationMode::SelectedClass,
+        OptimizationMode::Average,
+        OptimizationMode::Maximin,
+    ];
+
+    /// Returns the objective of the criterion in this mode, `class` is the selected class.
+    pub fn objective(self, kind: CriterionKind, class: usize) -> Box<dyn Objective> {
+        match self {
+            OptimizationMode::SelectedClass => Box::new(ClassCriterion { kind, class }),
+            OptimizationMode::Average => Box::new(MeanCriterion(kind)),
+            OptimizationMode::Maximin => Box::new(WorstClassCriterion(kind)),
+        }
+    }
+}
+
+impl Display for OptimizationMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OptimizationMode::SelectedClass => write!(f, "Selected class"),
+            OptimizationMode::Average => write!(f, "Average"),
+            OptimizationMode::Maximin => write!(f, "Maximin"),
+        }
+    }
+}
+
+impl std::str::FromStr for OptimizationMode {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|mode| mode.to_string().to_lowercase().replace(' ', "_") == value)
+            .ok_or_else(|| Error::InvalidArgument(format!("Unknown optimization mode {value}")))
+    }
+}
+
+/// Result of training with one point of parameter space.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Evaluation {
+    /// Value of the objective, `None` outside working space.
+    pub value: Option<f64>,
+    /// Best values of every criterion averaged over classes with working space
+    /// in the order of [`CriterionKind::ALL`], `None` if no class has one.
+    pub criteria: Vec<Option<f64>>,
+    /// Best value of the criterion of the objective for every class.
+    pub classes: Vec<Option<f64>>,
+}
+
+impl Evaluation {
+    /// Averages the best value of every criterion over classes that have one.
+    pub fn new(objective: &dyn Objective, criterias: &[Criteria]) -> Self {
+        Self {
+            value: objective.value(criterias),
+            classes: criterias
+                .iter()
+                .map(|c| c.best_criteria(objective.criterion()).map(|c| c.1))
+                .collect(),
+            criteria: CriterionKind::ALL
+                .iter()
+                .map(|&kind| {
+                    let values: Vec<f64> = criterias
+                        .iter()
+                        .filter_map(|c| c.best_criteria(kind).map(|c| c.1))
+                        .collect();
+
+                    (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64)
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Strategy {
+    /// Evaluates every point of the grid.
+    Grid,
+    /// Golden-section search of every parameter in turn, assuming a unimodal objective.
+    GoldenSection,
+    /// Searches every value of one parameter at a time until no parameter improves.
+    CoordinateDescent { sweeps: usize },
+    /// Evaluates random points, half of them mutations of the best point so far.
+    Random { samples: usize, seed: u64 },
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 4] = [
+        Strategy::Grid,
+        Strategy::GoldenSection,
+        Strategy::CoordinateDescent { sweeps: 5 },
+        Strategy::Random {
+            samples: 200,
+            seed: 1,
+        },
+    ];
+
+    fn same_kind(&self, other: &Strategy) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+}
+
+impl std::str::FromStr for Strategy {
+    type Err = Error;
+
+    /// Parses the snake case name of a strategy with its default settings.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|strategy| strategy.to_string().to_lowercase().replace(' ', "_") == value)
+            .ok_or_else(|| Error::InvalidArgument(format!("Unknown strategy {value}")))
+    }
+}
+
+impl Display for Strategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Strategy::Grid => write!(f, "Grid"),
+            Strategy::GoldenSection => write!(f, "Golden section"),
+            Strategy::CoordinateDescent { .. } => write!(f, "Coordinate descent"),
+            Strategy::Random { .. } => write!(f, "Random"),
+        }
+    }
+}
+
+/// Evaluated point of parameter space.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogEntry {
+    pub values: Vec<f64>,
+    pub evaluation: Evaluation,
+}
+
+/// Every point evaluated by optimization in the order of evaluation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptimizationLog {
+    pub objective: String,
+    pub strategy: Strategy,
+    pub parameters: Vec<Parameter>,
+    pub entries: Vec<LogEntry>,
+    pub lower_is_better: bool,
+}
+
+impl OptimizationLog {
+    /// Returns the entry with the best value of the objective.
+    pub fn best(&self) -> Option<&LogEntry> {
+        self.entries
+            .iter()
+            .filter_map(|entry| Some((entry, entry.evaluation.value?)))
+            .max_by(|(_, a), (_, b)| self.compare(*a, *b))
+            .map(|(entry, _)| entry)
+    }
+
+    fn compare(&self, a: f64, b: f64) -> std::cmp::Ordering {
+        if self.lower_is_better {
+            b.total_cmp(&a)
+        } else {
+            a.total_cmp(&b)
+        }
+    }
+
+    fn points(&self) -> Vec<[f64; 2]> {
+        self.entries
+            .iter()
+            .enumerate()
+            .map(|(step, entry)| [step as f64, entry.evaluation.value.unwrap_or(f64::NAN)])
+            .collect()
+    }
+
+    fn best_points(&self) -> Vec<[f64; 2]> {
+        let mut best: Option<f64> = None;
+
+        self.entries
+            .iter()
+            .enumerate()
+            .map(|(step, entry)| {
+                if let Some(value) = entry.evaluation.value {
+                    if best.is_none_or(|b| self.compare(value, b).is_gt()) {
+                        best = Some(value);
+                    }
+                }
+
+                [step as f64, best.unwrap_or(f64::NAN)]
+            })
+            .collect()
+    }
+
+    fn describe(&self, entry: &LogEntry) -> String {
+        self.parameters
+            .iter()
+            .zip(&entry.values)
+            .map(|(parameter, value)| format!("{parameter} = {value}"))
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
+}
+
+/// Searches `space` from `start` for the best value of the objective computed by `evaluate`.
+pub fn optimize(
+    space: &ParameterSpace,
+    strategy: Strategy,
+    objective: &dyn Objective,
+    start: &[f64],
+    evaluate: impl FnMut(&[f64]) -> Evaluation,
+) -> OptimizationLog {
+    let mut search = Search {
+        space,
+        lower_is_better: objective.lower_is_better(),
+        evaluate,
+        cache: HashMap::new(),
+        entries: Vec::new(),
+    };
+    let start = space.indices(start);
+
+    if !space.ranges.is_empty() {
+        match strategy {
+            Strategy::Grid => search.grid(),
+            Strategy::GoldenSection => search.golden_section(start),
+            Strategy::CoordinateDescent { sweeps } => search.coordinate_descent(start, sweeps),
+            Strategy::Random { samples, seed } => search.random(samples, seed),
+        }
+    }
+
+    OptimizationLog {
+        objective: objective.name(),
+        strategy,
+        parameters: space.parameters(),
+        entries: search.entries,
+        lower_is_better: search.lower_is_better,
+    }
+}
+
+/// State of a search over indices of parameter values.
+struct Search<'a, F> {
+    space: &'a ParameterSpace,
+    lower_is_better: bool,
+    evaluate: F,
+    cache: HashMap<Vec<usize>, Option<f64>>,
+    entries: Vec<LogEntry>,
+}
+
+impl<F: FnMut(&[f64]) -> Evaluation> Search<'_, F> {
+    /// Score of a point that is higher for better points, evaluating every point once.
+    fn score(&mut self, point: &[usize]) -> f64 {
+        if let Some(value) = self.cache.get(point) {
+            return self.to_score(*value);
+        }
+
+        let values = self.space.values(point);
+        let evaluation = (self.evaluate)(&values);
+        let value = evaluation.value;
+
+        self.cache.insert(point.to_vec(), value);
+        self.entries.push(LogEntry { values, evaluation });
+
+        self.to_score(value)
+    }
+
+    fn to_score(&self, value: Option<f64>) -> f64 {
+        match value {
+            Some(value) if value.is_nan() => f64::NEG_INFINITY,
+            Some(value) if self.lower_is_better => -value,
+            Some(value) => value,
+            None => f64::NEG_INFINITY,
+        }
+    }
+
+    fn grid(&mut self) {
+        let lens: Vec<usize> = self.space.ranges.iter().map(ParameterRange::len).collect();
+        let mut point = vec![0; lens.len()];
+
+        loop {
+            self.score(&point.clone());
+
+            let Some(axis) = (0..point.len()).find(|&axis| point[axis] + 1 < lens[axis]) else {
+                break;
+            };
+
+            point[axis] += 1;
+            point[..axis].iter_mut().for_each(|index| *index = 0);
+        }
+    }
+
+    /// Returns the best index of `axis` keeping other coordinates of `point`.
+    fn golden_section_axis(&mut self, point: &[usize], axis: usize) -> usize {
+        const RATIO: f64 = 0.618_033_988_749_895;
+
+        let at = |search: &mut Self, index: usize| {
+            let mut point = point.to_vec();
+            point[axis] = index;
+            search.score(&point)
+        };
+
+        let (mut low, mut high) = (0, self.space.ranges[axis].len() - 1);
+
+        while high - low > 2 {
+            let offset = ((high - low) as f64 * RATIO).round() as usize;
+            let left = (high - offset).max(low + 1);
+            let right = (low + offset).min(high - 1).max(left + 1);
+
+            if right >= high {
+                break;
+            }
+
+            if at(self, left) >= at(self, right) {
+                high = right;
+            } else {
+                low = left;
+            }
+        }
+
+        (low..=high)
+            .map(|index| (index, at(self, index)))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map_or(low, |(index, _)| index)
+    }
+
+    fn golden_section(&mut self, mut point: Vec<usize>) {
+        (0..point.len()).for_each(|axis| {
+            point[axis] = self.golden_section_axis(&point, axis);
+        });
+    }
+
+    fn coordinate_descent(&mut self, mut point: Vec<usize>, sweeps: usize) {
+        let mut best = self.score(&point);
+
+        for _ in 0..sweeps.max(1) {
+            let mut improved = false;
+
+            (0..point.len()).for_each(|axis| {
+                (0..self.space.ranges[axis].len()).for_each(|index| {
+                    let mut candidate = point.clone();
+                    candidate[axis] = index;
+                    let score = self.score(&candidate);
+
+                    if score > best {
+                        best = score;
+                        point = candidate;
+                        improved = true;
+                    }
+                });
+            });
+
+            if !improved {
+                break;
+            }
+        }
+    }
+
+    fn random(&mut self, samples: usize, seed: u64) {
+        let mut rng = XorShift::new(seed);
+        let lens: Vec<usize> = self.space.ranges.iter().map(ParameterRange::len).collect();
+        let mut best: Option<(Vec<usize>, f64)> = None;
+
+        (0..samples).for_each(|sample| {
+            let point: Vec<usize> = match &best {
+                Some((best, _)) if sample % 2 == 1 => best
+                    .iter()
+                    .zip(&lens)
+                    .map(|(&index, &len)| {
+                        let step = (len / 10).max(1) as i64;
+                        let shift = rng.below(2 * step as usize + 1) as i64 - step;
+                        (index as i64 + shift).clamp(0, len as i64 - 1) as usize
+                    })
+                    .collect(),
+                _ => lens.iter().map(|&len| rng.below(len)).collect(),
+            };
+
+            let score = self.score(&point);
+
+            if best.as_ref().is_none_or(|(_, best)| score > *best) {
+                best = Some((point, score));
+            }
+        });
+    }
+}
+
+/// Small deterministic pseudo-random generator, so searches can be repeated.
+struct XorShift(u64);
+
+impl XorShift {
+    fn new(seed: u64) -> Self {
+        Self(seed.max(1))
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+
+        (self.0 % bound.max(1) as u64) as usize
+    }
+}
+
+impl Show for OptimizationLog {
+    fn show(&self, ui: &mut Ui) {
+        ui.add(Label::new(format!(
+            "{} by {}, {} evaluations",
+            self.objective,
+            self.strategy,
+            self.entries.len()
+        )));
+
+        match self.best() {
+            Some(best) => ui.add(Label::new(format!(
+                "Best {}: {}",
+                best.evaluation.value.unwrap_or_default(),
+                self.describe(best)
+            ))),
+            None => ui.add(Label::new("No point has working space")),
+        };
+
+        Plot::new("Optimization log")
+            .legend(Legend::default())
+            .auto_bounds_x()
+            .auto_bounds_y()
+            .show(ui, |ui| {
+                ui.line(Line::new(self.points()).name(&self.objective));
+                ui.line(Line::new(self.best_points()).name("Best"));
+            });
+    }
+}
+
+impl ToFigure for OptimizationLog {
+    fn figure(&self) -> Figure {
+        Figure::new(vec![Panel::new(format!(
+            "{} by {}",
+            self.objective, self.strategy
+        ))
+        .line(FigureLine::new(self.objective.clone(), self.points()))
+        .line(FigureLine::new("Best", self.best_points()))])
+    }
+}
+
+impl ToTables for OptimizationLog {
+    fn tables(&self) -> Vec<Table> {
+        let parameters: Vec<String> = self.parameters.iter().map(|p| p.to_string()).collect();
+        let criteria: Vec<String> = CriterionKind::ALL.iter().map(|k| k.column()).collect();
+        let mut columns = vec!["step"];
+        columns.extend(parameters.iter().map(String::as_str));
+        columns.push("objective");
+        columns.extend(criteria.iter().map(String::as_str));
+
+        let mut table = Table::new("optimization_log", &columns);
+
+        self.entries.iter().enumerate().for_each(|(step, entry)| {
+            let mut row = vec![step.into()];
+            row.extend(entry.values.iter().map(|&value| value.into()));
+            row.push(entry.evaluation.value.unwrap_or(f64::NAN).into());
+            row.extend(entry.evaluation.criteria.iter().map(|&value| value.into()));
+
+            table.push(row);
+        });
+
+        vec![table]
+    }
+}
+
+/// Controls of the optimizer window.
+pub struct OptimizerControls {
+    pub space: ParameterSpace,
+    pub strategy: Strategy,
+    attribute: usize,
+}
+
+impl Default for OptimizerControls {
+    fn default() -> Self {
+        Self {
+            space: ParameterSpace::new(vec![ParameterRange::of(Parameter::Delta)]),
+            strategy: Strategy::Grid,
+            attribute: 0,
+        }
+    }
+}
+
+impl OptimizerControls {
+    /// Shows the controls and returns `true` when optimization is requested.
+    pub fn show(&mut self, attributes: usize, ui: &mut Ui) -> bool {
+        let mut removed = None;
+
+        self.space
+            .ranges
+            .iter_mut()
+            .enumerate()
+            .for_each(|(i, range)| {
+                let speed = range.step;
+
+                ui.horizontal(|ui| {
+                    ui.add(Label::new(range.parameter.to_string()));
+                    ui.add(DragValue::new(&mut range.min).speed(speed).prefix("from "));
+                    ui.add(DragValue::new(&mut range.max).speed(speed).prefix("to "));
+                    ui.add(
+                        DragValue::new(&mut range.step)
+                            .speed(0.01)
+                            .clamp_range(0.001..=f64::MAX)
+                            .prefix("step "),
+                    );
+
+                    if ui.add(Button::new("x")).clicked() {
+                        removed = Some(i);
+                    }
+                });
+            });
+
+        if let Some(i) = removed {
+            self.space.ranges.remove(i);
+        }
+
+        ui.horizontal(|ui| {
+            let mut add = |ui: &mut Ui, parameter: Parameter, label: &str| {
+                let exists = self.space.parameters().contains(&parameter);
+
+                if ui.add_enabled(!exists, Button::new(label)).clicked() {
+                    self.space.ranges.push(ParameterRange::of(parameter));
+                }
+            };
+
+            add(ui, Parameter::Delta, "Add delta");
+            add(ui, Parameter::SelectionLevel, "Add selection level");
+
+            ui.add(
+                DragValue::new(&mut self.attribute)
+                    .clamp_range(0..=attributes.saturating_sub(1))
+                    .prefix("attribute "),
+            );
+            add(
+                ui,
+                Parameter::AttributeDelta(self.attribute),
+                "Add attribute delta",
+            );
+        });
+
+        let mut run = false;
+
+        ui.horizontal(|ui| {
+            ComboBox::from_id_source("Strategy")
+                .selected_text(self.strategy.to_string())
+                .show_ui(ui, |ui| {
+                    Strategy::ALL.iter().for_each(|strategy| {
+                        if ui
+                            .selectable_label(
+                                self.strategy.same_kind(strategy),
+                                strategy.to_string(),
+                            )
+                            .clicked()
+                            && !self.strategy.same_kind(strategy)
+                        {
+                            self.strategy = *strategy;
+                        }
+                    });
+                });
+
+            match &mut self.strategy {
+                Strategy::CoordinateDescent { sweeps } => {
+                    ui.add(
+                        DragValue::new(sweeps)
+                            .clamp_range(1..=100)
+                            .prefix("sweeps "),
+                    );
+                }
+                Strategy::Random { samples, seed } => {
+                    ui.add(
+                        DragValue::new(samples)
+                            .clamp_range(1..=100000)
+                            .prefix("samples "),
+                    );
+                    ui.add(DragValue::new(seed).prefix("seed "));
+                }
+                Strategy::Grid => {
+                    ui.add(Label::new(format!("{} points", self.space.size())));
+                }
+                Strategy::GoldenSection => {}
+            }
+
+            run = ui
+                .add_enabled(!self.space.ranges.is_empty(), Button::new("Run"))
+                .clicked();
+        });
+
+        run
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Max;
+
+    impl Objective for Max {
+        fn name(&self) -> String {
+            "max".to_owned()
+        }
+
+        fn criterion(&self) -> CriterionKind {
+            CriterionKind::Shannon
+        }
+
+        fn value(&self, _: &[Criteria]) -> Option<f64> {
+            None
+        }
+    }
+
+    /// Concave function of two parameters with its maximum at (30, 0.4).
+    fn evaluate(values: &[f64]) -> Evaluation {
+        let y = values.get(1).copied().unwrap_or(0.4);
+
+        Evaluation {
+            value: Some(-(values[0] - 30.0).powi(2) - 100.0 * (y - 0.4).powi(2)),
+            criteria: Vec::new(),
+            classes: Vec::new(),
+        }
+    }
+
+    fn space() -> ParameterSpace {
+        ParameterSpace::new(vec![
+            ParameterRange::of(Parameter::Delta),
+            ParameterRange::of(Parameter::SelectionLevel),
+        ])
+    }
+
+    fn best(strategy: Strategy, space: &ParameterSpace) -> (Vec<f64>, usize) {
+        let log = optimize(space, strategy, &Max, &[0.0, 0.5], evaluate);
+        let best = log.best().unwrap().values.clone();
+
+        (best, log.entries.len())
+    }
+
+    fn assert_close(values: &[f64], expected: &[f64]) {
+        values
+            .iter()
+            .zip(expected)
+            .for_each(|(value, expected)| assert!((value - expected).abs() < 1e-9, "{values:?}"));
+    }
+
+    fn criterias() -> Vec<Criteria> {
+        let weights = crate::criteria::Weights {
+            priors: vec![1.0 / 3.0; 3],
+            cost_ratio: 1.0,
+        };
+
+        (0..3u32)
+            .map(|i| {
+                // Realizations of the class lie closer to its centre the higher the class
+                let distances: Vec<Vec<u32>> = (0..3)
+                    .map(|j| {
+                        if i == j {
+                            vec![0, 1, 4 - i]
+                        } else {
+                            vec![3, 6, 7]
+                        }
+                    })
+                    .collect();
+
+                Criteria::new(
+                    i as usize,
+                    &distances,
+                    10,
+                    &weights,
+                    &crate::working_space::WorkingSpace::default(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn modes_take_criteria_of_their_classes() {
+        let criterias = criterias();
+        let kind = CriterionKind::Shannon;
+        let value = |mode: OptimizationMode, class: usize| {
+            mode.objective(kind, class).value(&criterias).unwrap()
+        };
+        let classes: Vec<f64> = (0..3)
+            .map(|class| value(OptimizationMode::SelectedClass, class))
+            .collect();
+
+        assert_eq!(classes[1], criterias[1].best_criteria(kind).unwrap().1);
+        assert_eq!(
+            value(OptimizationMode::Average, 0),
+            classes.iter().sum::<f64>() / 3.0
+        );
+        assert_eq!(
+            value(OptimizationMode::Maximin, 0),
+            classes.iter().copied().fold(f64::INFINITY, f64::min)
+        );
+    }
+
+    #[test]
+    fn classes_without_working_space_are_left_out_of_averages() {
+        let mut criterias = criterias();
+        criterias.push(Criteria::new(
+            0,
+            &[vec![0, 1], vec![3, 6]],
+            10,
+            &crate::criteria::Weights {
+                priors: vec![0.5; 2],
+                cost_ratio: 1.0,
+            },
+            &crate::working_space::WorkingSpace {
+                max_radius: Some(0),
+                ..Default::default()
+            },
+        ));
+        let kind = CriterionKind::TotalError;
+        let evaluation = Evaluation::new(&MeanCriterion(kind), &criterias);
+
+        let values: Vec<f64> = criterias[..3]
+            .iter()
+            .map(|c| c.best_criteria(kind).unwrap().1)
+            .collect();
+        assert_eq!(
+            evaluation.criteria[kind.index()],
+            Some(values.iter().sum::<f64>() / 3.0)
+        );
+        assert_eq!(evaluation.value, None);
+        assert_eq!(
+            Evaluation::new(&MeanCriterion(kind), &[]).criteria[kind.index()],
+            None
+        );
+    }
+
+    #[test]
+    fn worst_class_of_lower_is_better_criterion_has_the_highest_value() {
+        let criterias = criterias();
+        let kind = CriterionKind::TotalError;
+        let worst = WorstClassCriterion(kind).value(&criterias).unwrap();
+
+        assert!(criterias
+            .iter()
+            .all(|c| c.best_criteria(kind).unwrap().1 <= worst));
+    }
+
+    #[test]
+    fn names_are_parsed() {
+        assert_eq!("delta_12".parse(), Ok(Parameter::AttributeDelta(12)));
+        assert_eq!("selection_level".parse(), Ok(Parameter::SelectionLevel));
+        assert!("delta_x".parse::<Parameter>().is_err());
+        assert_eq!("golden_section".parse(), Ok(Strategy::GoldenSection));
+        assert!("annealing".parse::<Strategy>().is_err());
+        assert_eq!(
+            "selected_class".parse(),
+            Ok(OptimizationMode::SelectedClass)
+        );
+    }
+
+    #[test]
+    fn ranges_include_both_ends() {
+        let range = ParameterRange::of(Parameter::SelectionLevel);
+
+        assert_eq!(range.len(), 19);
+        assert!((range.value(18) - 0.95).abs() < 1e-9);
+        assert_eq!(range.index_of(0.5), 9);
+        assert_eq!(ParameterRange::of(Parameter::Delta).len(), 256);
+    }
+
+    #[test]
+    fn grid_evaluates_every_point() {
+        let (values, evaluations) = best(Strategy::Grid, &space());
+
+        assert_close(&values, &[30.0, 0.4]);
+        assert_eq!(evaluations, 256 * 19);
+    }
+
+    #[test]
+    fn golden_section_finds_maximum_of_unimodal_objective() {
+        let (values, evaluations) = best(Strategy::GoldenSection, &space());
+
+        assert_close(&values, &[30.0, 0.4]);
+        assert!(evaluations < 60);
+    }
+
+    #[test]
+    fn coordinate_descent_finds_maximum() {
+        let (values, evaluations) = best(Strategy::CoordinateDescent { sweeps: 5 }, &space());
+
+        assert_close(&values, &[30.0, 0.4]);
+        assert!(evaluations < 255 * 19);
+    }
+
+    #[test]
+    fn random_search_is_repeatable() {
+        let strategy = Strategy::Random {
+            samples: 300,
+            seed: 7,
+        };
+        let space = ParameterSpace::new(vec![ParameterRange::of(Parameter::Delta)]);
+
+        assert_eq!(best(strategy, &space), best(strategy, &space));
+        assert_close(&best(strategy, &space).0, &[30.0]);
+    }
+
+    #[test]
+    fn lower_is_better_objectives_are_minimized() {
+        let log = OptimizationLog {
+            objective: String::new(),
+            strategy: Strategy::Grid,
+            parameters: vec![Parameter::Delta],
+            entries: [Some(2.0), None, Some(1.0)]
+                .into_iter()
+                .enumerate()
+                .map(|(i, value)| LogEntry {
+                    values: vec![i as f64],
+                    evaluation: Evaluation {
+                        value,
+                        criteria: Vec::new(),
+                        classes: Vec::new(),
+                    },
+                })
+                .collect(),
+            lower_is_better: true,
+        };
+
+        assert_eq!(log.best().unwrap().values, [2.0]);
+    }
+}
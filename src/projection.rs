@@ -0,0 +1,312 @@
+use std::f64::consts::TAU;
+
+use eframe::egui::Ui;
+use egui_plot::{Legend, Line, MarkerShape, Plot, PlotPoints, Points};
+
+use crate::{
+    draw::Show,
+    figure::{Figure, Panel, ToFigure, PALETTE},
+    table::{Table, ToTables},
+};
+
+/// All classes placed on the plane by multidimensional scaling of distances between centres.
+#[derive(Debug, Default)]
+pub struct Projection {
+    pub centres: Vec<[f64; 2]>,
+    /// Radius of the optimal container of every class, `None` without working space.
+    pub radii: Vec<Option<f64>>,
+    /// Realizations of every class placed by their distances to all centres.
+    pub realizations: Vec<Vec<[f64; 2]>>,
+}
+
+impl Projection {
+    /// Places centres by classical multidimensional scaling of `distances` between them
+    /// and realizations by `distances_to_realizations[centre][class]`.
+    pub fn new(
+        distances: &[Vec<u32>],
+        distances_to_realizations: &[Vec<Vec<u32>>],
+        radii: Vec<Option<f64>>,
+    ) -> Self {
+        let n = distances.len();
+        if n < 2 {
+            return Self::default();
+        }
+
+        let squared: Vec<Vec<f64>> = distances
+            .iter()
+            .map(|row| row.iter().map(|&d| (d as f64).powi(2)).collect())
+            .collect();
+        let (eigenvalues, eigenvectors) = symmetric_eigen(double_centre(&squared));
+
+        let mut order: Vec<usize> = (0..n).collect();
+        order.sort_by(|&a, &b| eigenvalues[b].total_cmp(&eigenvalues[a]));
+        let axes: Vec<(f64, Vec<f64>)> = order
+            .iter()
+            .take(2)
+            .map(|&k| {
+                let eigenvalue = eigenvalues[k].max(0.0);
+                let axis = (0..n)
+                    .map(|i| eigenvectors[i][k] * eigenvalue.sqrt())
+                    .collect();
+                (eigenvalue, axis)
+            })
+            .collect();
+
+        let centres: Vec<[f64; 2]> = (0..n).map(|i| [axes[0].1[i], axes[1].1[i]]).collect();
+        let norms: Vec<f64> = centres
+            .iter()
+            .map(|c| c[0].powi(2) + c[1].powi(2))
+            .collect();
+
+        // Gower's formula adds a point with squared distances `a` to the centres:
+        // y = Λ⁻¹ Xᵀ (|x|² - a) / 2, the centres are centred so the unknown |y|² cancels.
+        let place = |squared: &[f64]| {
+            let coordinate = |(eigenvalue, axis): &(f64, Vec<f64>)| {
+                if *eigenvalue <= f64::EPSILON {
+                    return 0.0;
+                }
+
+                (0..n)
+                    .map(|i| axis[i] * (norms[i] - squared[i]))
+                    .sum::<f64>()
+                    / (2.0 * eigenvalue)
+            };
+
+            [coordinate(&axes[0]), coordinate(&axes[1])]
+        };
+
+        let realizations = (0..n)
+            .map(|class| {
+                let count = distances_to_realizations[0][class].len();
+
+                (0..count)
+                    .map(|r| {
+                        let squared: Vec<f64> = (0..n)
+                            .map(|centre| {
+                                (distances_to_realizations[centre][class][r] as f64).powi(2)
+                            })
+                            .collect();
+                        place(&squared)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            centres,
+            radii,
+            realizations,
+        }
+    }
+}
+
+/// Returns `-J D J / 2` where `J` centres rows and columns of squared distances `D`.
+fn double_centre(squared: &[Vec<f64>]) -> Vec<Vec<f64>> {
+    let n = squared.len();
+    let means: Vec<f64> = squared
+        .iter()
+        .map(|row| row.iter().sum::<f64>() / n as f64)
+        .collect();
+    let mean = means.iter().sum::<f64>() / n as f64;
+
+    (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| -(squared[i][j] - means[i] - means[j] + mean) / 2.0)
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns eigenvalues and eigenvectors, as columns, of a symmetric matrix
+/// found by cyclic Jacobi rotations.
+fn symmetric_eigen(mut a: Vec<Vec<f64>>) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let n = a.len();
+    let mut v: Vec<Vec<f64>> = (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect();
+
+    for _ in 0..100 {
+        let off_diagonal: f64 = (0..n)
+            .flat_map(|i| (0..n).filter(move |&j| j != i).map(move |j| (i, j)))
+            .map(|(i, j)| a[i][j].powi(2))
+            .sum();
+        if off_diagonal < 1e-18 {
+            break;
+        }
+
+        for p in 0..n {
+            for q in p + 1..n {
+                if a[p][q] == 0.0 {
+                    continue;
+                }
+
+                let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta.powi(2) + 1.0).sqrt());
+                let c = 1.0 / (t.powi(2) + 1.0).sqrt();
+                let s = t * c;
+                let rotate = |x: f64, y: f64| (c * x - s * y, s * x + c * y);
+
+                for row in a.iter_mut() {
+                    (row[p], row[q]) = rotate(row[p], row[q]);
+                }
+                let (upper, lower) = a.split_at_mut(q);
+                for (x, y) in upper[p].iter_mut().zip(lower[0].iter_mut()) {
+                    (*x, *y) = rotate(*x, *y);
+                }
+                for row in v.iter_mut() {
+                    (row[p], row[q]) = rotate(row[p], row[q]);
+                }
+            }
+        }
+    }
+
+    ((0..n).map(|i| a[i][i]).collect(), v)
+}
+
+fn circle(center: [f64; 2], radius: f64) -> Vec<[f64; 2]> {
+    (0..=100)
+        .map(|i| {
+            let t = i as f64 / 100.0 * TAU;
+            [center[0] + radius * t.cos(), center[1] + radius * t.sin()]
+        })
+        .collect()
+}
+
+impl Show for Projection {
+    fn show(&self, ui: &mut Ui) {
+        if self.centres.is_empty() {
+            ui.label("Nothing to show");
+            return;
+        }
+
+        Plot::new("Projection")
+            .legend(Legend::default())
+            .data_aspect(1.0)
+            .show(ui, |ui| {
+                self.centres
+                    .iter()
+                    .enumerate()
+                    .for_each(|(class, &centre)| {
+                        let color = PALETTE[class % PALETTE.len()];
+                        let name = format!("Class {class}");
+
+                        ui.points(
+                            Points::new(self.realizations[class].clone())
+                                .name(&name)
+                                .radius(1.5)
+                                .color(color),
+                        );
+                        ui.points(
+                            Points::new(vec![centre])
+                                .name(&name)
+                                .shape(MarkerShape::Diamond)
+                                .radius(5.0)
+                                .color(color),
+                        );
+
+                        if let Some(radius) = self.radii[class] {
+                            ui.line(
+                                Line::new(PlotPoints::from(circle(centre, radius)))
+                                    .name(&name)
+                                    .color(color),
+                            );
+                        }
+                    });
+            });
+    }
+}
+
+impl ToFigure for Projection {
+    fn figure(&self) -> Figure {
+        let mut panel = Panel::new("Projection of all classes").equal_aspect();
+
+        for (class, &centre) in self.centres.iter().enumerate() {
+            let color = PALETTE[class % PALETTE.len()];
+
+            panel = panel.circle(centre, 1.0, color, true);
+            if let Some(radius) = self.radii[class] {
+                panel = panel.circle(centre, radius, color, false);
+            }
+            for &realization in &self.realizations[class] {
+                panel = panel.circle(realization, 0.5, color, false);
+            }
+        }
+
+        Figure::new(vec![panel])
+    }
+}
+
+impl ToTables for Projection {
+    fn tables(&self) -> Vec<Table> {
+        let mut table = Table::new("projection", &["class", "realization", "x", "y"]);
+
+        self.centres.iter().enumerate().for_each(|(class, centre)| {
+            table.push(vec![
+                class.into(),
+                String::new().into(),
+                centre[0].into(),
+                centre[1].into(),
+            ]);
+            self.realizations[class]
+                .iter()
+                .enumerate()
+                .for_each(|(realization, position)| {
+                    table.push(vec![
+                        class.into(),
+                        realization.into(),
+                        position[0].into(),
+                        position[1].into(),
+                    ]);
+                });
+        });
+
+        vec![table]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn distance(a: [f64; 2], b: [f64; 2]) -> f64 {
+        (a[0] - b[0]).hypot(a[1] - b[1])
+    }
+
+    #[test]
+    fn planar_distances_are_reproduced() {
+        // Centres at (0, 0), (3, 0) and (0, 4), one realization of class 0 at (3, 4)
+        let distances = vec![vec![0, 3, 4], vec![3, 0, 5], vec![4, 5, 0]];
+        let distances_to_realizations = vec![
+            vec![vec![5], vec![], vec![]],
+            vec![vec![4], vec![], vec![]],
+            vec![vec![3], vec![], vec![]],
+        ];
+
+        let projection = Projection::new(&distances, &distances_to_realizations, vec![None; 3]);
+
+        distances.iter().enumerate().for_each(|(i, row)| {
+            row.iter().enumerate().for_each(|(j, &d)| {
+                let projected = distance(projection.centres[i], projection.centres[j]);
+                assert!((projected - d as f64).abs() < 1e-9);
+            });
+        });
+
+        let realization = projection.realizations[0][0];
+        [5.0, 4.0, 3.0].iter().enumerate().for_each(|(i, &d)| {
+            assert!((distance(realization, projection.centres[i]) - d).abs() < 1e-9);
+        });
+    }
+
+    #[test]
+    fn two_classes_lie_on_a_line() {
+        let projection = Projection::new(
+            &[vec![0, 10], vec![10, 0]],
+            &[vec![vec![], vec![]], vec![vec![], vec![]]],
+            vec![Some(2.0), None],
+        );
+
+        assert!((distance(projection.centres[0], projection.centres[1]) - 10.0).abs() < 1e-9);
+        assert_eq!(projection.centres[0][1], 0.0);
+    }
+}
@@ -0,0 +1,320 @@
+use eframe::egui::{Button, Grid, Label, TextEdit, Ui};
+
+use crate::{
+    criteria::Criteria,
+    criterion::CriterionKind,
+    sk::SK,
+    table::{Table, ToTables},
+};
+
+/// Training quality of a class at its optimal radius.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ClassQuality {
+    pub class: usize,
+    pub closest: usize,
+    pub distance: u32,
+    pub radius: Option<usize>,
+    pub d1: Option<f64>,
+    pub alpha: Option<f64>,
+    pub beta: Option<f64>,
+    pub d2: Option<f64>,
+    pub kullback: Option<f64>,
+    pub shannon: Option<f64>,
+}
+
+impl ClassQuality {
+    pub fn new(
+        class: usize,
+        criteria: &Criteria,
+        sk: &SK,
+        radius_criterion: CriterionKind,
+    ) -> Self {
+        let radius = criteria
+            .best_criteria(radius_criterion)
+            .map(|(radius, _)| radius);
+        let characteristics = radius.map(|radius| &criteria.characteristics[radius]);
+
+        Self {
+            class,
+            closest: sk.closest,
+            distance: sk.distance,
+            radius,
+            d1: characteristics.map(|c| c.d1),
+            alpha: characteristics.map(|c| c.alpha),
+            beta: characteristics.map(|c| c.beta),
+            d2: characteristics.map(|c| c.d2),
+            kullback: criteria
+                .best_criteria(CriterionKind::Kullback)
+                .map(|(_, k)| k),
+            shannon: criteria
+                .best_criteria(CriterionKind::Shannon)
+                .map(|(_, s)| s),
+        }
+    }
+
+    fn cells(&self) -> [String; COLUMNS.len()] {
+        let format = |value: Option<f64>| value.map_or(String::new(), |v| format!("{v:.4}"));
+
+        [
+            self.class.to_string(),
+            self.closest.to_string(),
+            self.distance.to_string(),
+            self.radius.map_or(String::new(), |r| r.to_string()),
+            format(self.d1),
+            format(self.alpha),
+            format(self.beta),
+            format(self.d2),
+            format(self.kullback),
+            format(self.shannon),
+        ]
+    }
+
+    fn cmp_by(&self, other: &Self, column: usize) -> std::cmp::Ordering {
+        let optional = |a: Option<f64>, b: Option<f64>| match (a, b) {
+            (Some(a), Some(b)) => a.total_cmp(&b),
+            (a, b) => a.is_some().cmp(&b.is_some()),
+        };
+
+        match column {
+            0 => self.class.cmp(&other.class),
+            1 => self.closest.cmp(&other.closest),
+            2 => self.distance.cmp(&other.distance),
+            3 => self.radius.cmp(&other.radius),
+            4 => optional(self.d1, other.d1),
+            5 => optional(self.alpha, other.alpha),
+            6 => optional(self.beta, other.beta),
+            7 => optional(self.d2, other.d2),
+            8 => optional(self.kullback, other.kullback),
+            _ => optional(self.shannon, other.shannon),
+        }
+    }
+}
+
+const COLUMNS: [&str; 10] = [
+    "Class", "Closest", "Distance", "Radius", "D1", "Alpha", "Beta", "D2", "Kullback", "Shannon",
+];
+
+/// Table of training quality with one row per class.
+#[derive(Default)]
+pub struct QualityTable {
+    pub rows: Vec<ClassQuality>,
+    sort_column: usize,
+    descending: bool,
+    path: String,
+    message: Option<String>,
+}
+
+impl QualityTable {
+    pub fn set_rows(&mut self, criterias: &[Criteria], sk: &[SK], radius_criterion: CriterionKind) {
+        self.rows = criterias
+            .iter()
+            .zip(sk)
+            .enumerate()
+            .map(|(i, (criteria, sk))| ClassQuality::new(i, criteria, sk, radius_criterion))
+            .collect();
+
+        self.sort();
+    }
+
+    pub fn sort_by(&mut self, column: usize) {
+        if self.sort_column == column {
+            self.descending = !self.descending;
+        } else {
+            self.sort_column = column;
+            self.descending = false;
+        }
+
+        self.sort();
+    }
+
+    fn sort(&mut self) {
+        let column = self.sort_column;
+        let descending = self.descending;
+
+        self.rows.sort_by(|a, b| {
+            let ordering = a.cmp_by(b, column);
+
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+    }
+
+    pub fn show(&mut self, ui: &mut Ui) {
+        if self.rows.is_empty() {
+            ui.add(Label::new("No classes to show"));
+            return;
+        }
+
+        let mut clicked = None;
+
+        Grid::new("Quality table").striped(true).show(ui, |ui| {
+            COLUMNS.iter().enumerate().for_each(|(i, column)| {
+                let label = match (self.sort_column == i, self.descending) {
+                    (true, false) => format!("{column} ⏶"),
+                    (true, true) => format!("{column} ⏷"),
+                    _ => column.to_string(),
+                };
+
+                if ui.selectable_label(self.sort_column == i, label).clicked() {
+                    clicked = Some(i);
+                }
+            });
+            ui.end_row();
+
+            self.rows.iter().for_each(|row| {
+                row.cells().iter().for_each(|cell| {
+                    ui.add(Label::new(cell));
+                });
+                ui.end_row();
+            });
+        });
+
+        if let Some(column) = clicked {
+            self.sort_by(column);
+        }
+
+        let table = self.table();
+
+        ui.horizontal(|ui| {
+            if ui.add(Button::new("Copy CSV")).clicked() {
+                ui.output_mut(|o| o.copied_text = table.to_csv());
+            }
+
+            if ui.add(Button::new("Copy Markdown")).clicked() {
+                ui.output_mut(|o| o.copied_text = table.to_markdown());
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.add(TextEdit::singleline(&mut self.path));
+
+            if ui.add(Button::new("Export CSV")).clicked() {
+                self.export(table.to_csv());
+            }
+
+            if ui.add(Button::new("Export Markdown")).clicked() {
+                self.export(table.to_markdown());
+            }
+        });
+
+        if let Some(message) = self.message.clone() {
+            ui.horizontal(|ui| {
+                ui.add(Label::new(message));
+
+                if ui.add(Button::new("x")).clicked() {
+                    self.message = None;
+                }
+            });
+        }
+    }
+
+    fn export(&mut self, contents: String) {
+        self.message = Some(match std::fs::write(&self.path, contents) {
+            Ok(()) => format!("Saved to {}", self.path),
+            Err(err) => format!("Error: {err}"),
+        });
+    }
+}
+
+impl ToTables for QualityTable {
+    fn tables(&self) -> Vec<Table> {
+        vec![self.table()]
+    }
+}
+
+impl QualityTable {
+    fn table(&self) -> Table {
+        let mut table = Table::new(
+            "quality",
+            &[
+                "class", "closest", "distance", "radius", "d1", "alpha", "beta", "d2", "kullback",
+                "shannon",
+            ],
+        );
+
+        self.rows.iter().for_each(|row| {
+            table.push(vec![
+                row.class.into(),
+                row.closest.into(),
+                row.distance.into(),
+                row.radius.into(),
+                row.d1.into(),
+                row.alpha.into(),
+                row.beta.into(),
+                row.d2.into(),
+                row.kullback.into(),
+                row.shannon.into(),
+            ]);
+        });
+
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> QualityTable {
+        QualityTable {
+            rows: vec![
+                ClassQuality {
+                    class: 0,
+                    closest: 1,
+                    distance: 62,
+                    radius: Some(42),
+                    d1: Some(0.9),
+                    alpha: Some(0.1),
+                    beta: Some(0.25),
+                    d2: Some(0.75),
+                    kullback: Some(1.5),
+                    shannon: Some(0.4),
+                },
+                ClassQuality {
+                    class: 1,
+                    closest: 0,
+                    distance: 62,
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn csv_has_header_and_row_per_class() {
+        assert_eq!(
+            table().table().to_csv(),
+            "class,closest,distance,radius,d1,alpha,beta,d2,kullback,shannon\n\
+             0,1,62,42,0.9,0.1,0.25,0.75,1.5,0.4\n\
+             1,0,62,,,,,,,\n"
+        );
+    }
+
+    #[test]
+    fn markdown_has_separator_after_header() {
+        let markdown = table().table().to_markdown();
+        let lines: Vec<&str> = markdown.lines().collect();
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(
+            lines[1],
+            "| --- | --- | --- | --- | --- | --- | --- | --- | --- | --- |"
+        );
+        assert_eq!(lines[3], "| 1 | 0 | 62 |  |  |  |  |  |  |  |");
+    }
+
+    #[test]
+    fn sorting_twice_reverses_order() {
+        let mut table = table();
+
+        table.sort_by(9);
+        assert_eq!(table.rows[0].class, 1);
+
+        table.sort_by(9);
+        assert_eq!(table.rows[0].class, 0);
+    }
+}
@@ -0,0 +1,333 @@
+use eframe::egui::{ComboBox, DragValue, Ui};
+
+use crate::{
+    corridor::Statistics,
+    criteria::Weights,
+    criterion::CriterionKind,
+    optimizer::{OptimizationMode, Parameter, ParameterRange},
+    table::{Table, ToTables},
+    working_space::WorkingSpace,
+};
+
+/// Training parameters chosen by the user.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    /// Criterion that selects the radius of containers.
+    pub radius_criterion: CriterionKind,
+    /// Criterion maximized by delta optimization.
+    pub objective: CriterionKind,
+    /// Classes the optimized criterion is taken from.
+    pub optimization_mode: OptimizationMode,
+    /// Relative prior weight of every training class.
+    pub prior_weights: Vec<f64>,
+    /// Cost of missing a class (alpha) relative to a false alarm (beta).
+    pub cost_ratio: f64,
+    pub working_space: WorkingSpace,
+    /// Share of realizations in allowances above which a bit of a reference vector is set.
+    pub selection_level: f64,
+    /// Centre and units of delta of the corridor.
+    pub statistics: Statistics,
+    /// Deltas searched by delta optimization.
+    pub delta_range: ParameterRange,
+    /// Attributes skipped by binary representations and distances.
+    pub excluded_attributes: Vec<usize>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            radius_criterion: CriterionKind::Kullback,
+            objective: CriterionKind::Shannon,
+            optimization_mode: OptimizationMode::Average,
+            prior_weights: Vec::new(),
+            cost_ratio: 1.0,
+            working_space: WorkingSpace::default(),
+            selection_level: 0.5,
+            statistics: Statistics::default(),
+            delta_range: ParameterRange::of(Parameter::Delta),
+            excluded_attributes: Vec::new(),
+        }
+    }
+}
+
+impl Settings {
+    /// Keeps a weight for every one of `classes` classes, new classes get weight `1.0`.
+    pub fn set_classes(&mut self, classes: usize) {
+        self.prior_weights.resize(classes, 1.0);
+    }
+
+    /// Removes the weight of a deleted class, later classes keep their weights.
+    pub fn forget_class(&mut self, class: usize) {
+        if class < self.prior_weights.len() {
+            self.prior_weights.remove(class);
+        }
+    }
+
+    /// Returns prior probabilities of classes proportional to their weights.
+    pub fn priors(&self) -> Vec<f64> {
+        let classes = self.prior_weights.len();
+        let sum: f64 = self.prior_weights.iter().sum();
+
+        if sum > 0.0 {
+            self.prior_weights
+                .iter()
+                .map(|weight| weight / sum)
+                .collect()
+        } else {
+            vec![1.0 / classes as f64; classes]
+        }
+    }
+
+    pub fn weights(&self) -> Weights {
+        Weights {
+            priors: self.priors(),
+            cost_ratio: self.cost_ratio,
+        }
+    }
+
+    /// Shows the controls and returns `true` if any setting has changed.
+    pub fn show(&mut self, ui: &mut Ui) -> bool {
+        let mut changed = false;
+
+        ui.horizontal(|ui| {
+            changed |= criterion_combo_box("Radius criterion", &mut self.radius_criterion, ui);
+        });
+
+        ui.horizontal(|ui| {
+            changed |= criterion_combo_box("Optimization criterion", &mut self.objective, ui);
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Optimize for");
+            ComboBox::from_id_source("Optimization mode")
+                .selected_text(self.optimization_mode.to_string())
+                .show_ui(ui, |ui| {
+                    OptimizationMode::ALL.iter().for_each(|&mode| {
+                        changed |= ui
+                            .selectable_value(&mut self.optimization_mode, mode, mode.to_string())
+                            .changed();
+                    });
+                });
+        });
+
+        let tolerance = self.statistics.tolerance;
+        if self.statistics.show(ui) {
+            if self.statistics.tolerance != tolerance {
+                self.delta_range.max = self.statistics.tolerance.max_delta();
+            }
+            changed = true;
+        }
+
+        ui.horizontal(|ui| {
+            ui.label("Optimize delta from");
+            ui.add(DragValue::new(&mut self.delta_range.min).clamp_range(0.0..=f64::MAX));
+            ui.label("to");
+            ui.add(DragValue::new(&mut self.delta_range.max).clamp_range(0.0..=f64::MAX));
+            ui.label("by");
+            ui.add(
+                DragValue::new(&mut self.delta_range.step)
+                    .speed(0.01)
+                    .clamp_range(0.01..=f64::MAX),
+            );
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Cost of alpha to beta");
+            changed |= ui
+                .add(
+                    DragValue::new(&mut self.cost_ratio)
+                        .speed(0.05)
+                        .clamp_range(0.01..=100.0),
+                )
+                .changed();
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Selection level");
+            changed |= ui
+                .add(
+                    DragValue::new(&mut self.selection_level)
+                        .speed(0.01)
+                        .clamp_range(0.0..=1.0),
+                )
+                .changed();
+        });
+
+        ui.collapsing("Working space", |ui| {
+            changed |= self.working_space.show(ui);
+        });
+
+        if !self.prior_weights.is_empty() {
+            let priors = self.priors();
+
+            ui.horizontal_wrapped(|ui| {
+                ui.label("Prior weights");
+                (0..self.prior_weights.len()).for_each(|class| {
+                    changed |= ui
+                        .add(
+                            DragValue::new(&mut self.prior_weights[class])
+                                .speed(0.05)
+                                .clamp_range(0.0..=100.0)
+                                .prefix(format!("{class}: ")),
+                        )
+                        .on_hover_text(format!("Prior probability {:.4}", priors[class]))
+                        .changed();
+                });
+            });
+        }
+
+        changed
+    }
+}
+
+impl ToTables for Settings {
+    fn tables(&self) -> Vec<Table> {
+        let mut settings = Table::new("settings", &["parameter", "value"]);
+        settings.push(vec![
+            "radius_criterion".to_owned().into(),
+            self.radius_criterion.column().into(),
+        ]);
+        settings.push(vec![
+            "objective".to_owned().into(),
+            self.objective.column().into(),
+        ]);
+        settings.push(vec![
+            "optimization_mode".to_owned().into(),
+            self.optimization_mode
+                .to_string()
+                .to_lowercase()
+                .replace(' ', "_")
+                .into(),
+        ]);
+        settings.push(vec!["cost_ratio".to_owned().into(), self.cost_ratio.into()]);
+        settings.push(vec![
+            "selection_level".to_owned().into(),
+            self.selection_level.into(),
+        ]);
+        settings.push(vec![
+            "centre".to_owned().into(),
+            self.statistics
+                .centre
+                .to_string()
+                .to_lowercase()
+                .replace(' ', "_")
+                .into(),
+        ]);
+        settings.push(vec!["trim".to_owned().into(), self.statistics.trim.into()]);
+        settings.push(vec![
+            "tolerance".to_owned().into(),
+            self.statistics
+                .tolerance
+                .to_string()
+                .to_lowercase()
+                .replace(' ', "_")
+                .into(),
+        ]);
+        settings.push(vec![
+            "quantile".to_owned().into(),
+            self.statistics.quantile.into(),
+        ]);
+        settings.push(vec![
+            "delta_min".to_owned().into(),
+            self.delta_range.min.into(),
+        ]);
+        settings.push(vec![
+            "delta_max".to_owned().into(),
+            self.delta_range.max.into(),
+        ]);
+        settings.push(vec![
+            "delta_step".to_owned().into(),
+            self.delta_range.step.into(),
+        ]);
+        settings.push(vec![
+            "excluded_attributes".to_owned().into(),
+            self.excluded_attributes
+                .iter()
+                .map(usize::to_string)
+                .collect::<Vec<String>>()
+                .join(" ")
+                .into(),
+        ]);
+
+        let mut priors = Table::new("priors", &["class", "weight", "prior"]);
+        self.priors()
+            .iter()
+            .enumerate()
+            .for_each(|(class, &prior)| {
+                priors.push(vec![
+                    class.into(),
+                    self.prior_weights[class].into(),
+                    prior.into(),
+                ]);
+            });
+
+        let mut tables = vec![settings, priors];
+        tables.extend(self.working_space.tables());
+        tables
+    }
+}
+
+fn criterion_combo_box(label: &str, kind: &mut CriterionKind, ui: &mut Ui) -> bool {
+    let mut changed = false;
+
+    ui.label(label);
+    ComboBox::from_id_source(label)
+        .selected_text(kind.to_string())
+        .show_ui(ui, |ui| {
+            CriterionKind::ALL.iter().for_each(|&option| {
+                changed |= ui
+                    .selectable_value(kind, option, option.to_string())
+                    .changed();
+            });
+        });
+
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn priors_are_proportional_to_weights() {
+        let settings = Settings {
+            prior_weights: vec![1.0, 3.0],
+            ..Default::default()
+        };
+
+        assert_eq!(settings.priors(), [0.25, 0.75]);
+    }
+
+    #[test]
+    fn new_classes_get_unit_weight() {
+        let mut settings = Settings {
+            prior_weights: vec![3.0],
+            ..Default::default()
+        };
+        settings.set_classes(3);
+
+        assert_eq!(settings.priors(), [0.6, 0.2, 0.2]);
+    }
+
+    #[test]
+    fn deleted_class_takes_its_weight_along() {
+        let mut settings = Settings {
+            prior_weights: vec![1.0, 2.0, 3.0],
+            ..Default::default()
+        };
+        settings.forget_class(0);
+        settings.set_classes(2);
+
+        assert_eq!(settings.prior_weights, [2.0, 3.0]);
+    }
+
+    #[test]
+    fn zero_weights_fall_back_to_equal_priors() {
+        let settings = Settings {
+            prior_weights: vec![0.0, 0.0],
+            ..Default::default()
+        };
+
+        assert_eq!(settings.priors(), [0.5, 0.5]);
+    }
+}
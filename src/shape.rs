@@ -0,0 +1,90 @@
+use std::fmt::Display;
+
+use crate::{class_data::TextureData, error::Error};
+
+/// Dimensions of a class matrix.
+///
+/// Every row of a class image is one realization and every column is one attribute,
+/// so the image width is the number of attributes and its height is the number of realizations.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Shape {
+    pub attributes: usize,
+    pub realizations: usize,
+}
+
+impl Shape {
+    pub fn new(attributes: usize, realizations: usize) -> Self {
+        Self {
+            attributes,
+            realizations,
+        }
+    }
+
+    /// Number of values in a matrix of this shape.
+    pub fn len(&self) -> usize {
+        self.attributes * self.realizations
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Size of the image as `[width, height]`.
+    pub fn image_size(&self) -> [usize; 2] {
+        [self.attributes, self.realizations]
+    }
+
+    /// Checks that `bytes` hold a whole matrix of this shape.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `bytes` does not match the shape.
+    pub fn validate(&self, bytes: &[u8]) {
+        assert_eq!(
+            bytes.len(),
+            self.len(),
+            "matrix of {} values does not match shape {self}",
+            bytes.len()
+        );
+    }
+}
+
+/// Checks that `matrices` and `vectors` have `attributes` attributes.
+///
+/// Fails with the first number of attributes that differs.
+pub fn check_attributes(
+    matrices: &[TextureData],
+    vectors: &[TextureData],
+    attributes: usize,
+) -> Result<(), Error> {
+    matrices
+        .iter()
+        .map(|matrix| matrix.shape().attributes)
+        .chain(vectors.iter().map(|vector| vector.bytes.len()))
+        .try_for_each(|got| {
+            if got == attributes {
+                Ok(())
+            } else {
+                Err(Error::ShapeMismatch {
+                    expected: attributes,
+                    got,
+                })
+            }
+        })
+}
+
+impl From<[usize; 2]> for Shape {
+    fn from([attributes, realizations]: [usize; 2]) -> Self {
+        Self::new(attributes, realizations)
+    }
+}
+
+impl Display for Shape {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} attributes x {} realizations",
+            self.attributes, self.realizations
+        )
+    }
+}
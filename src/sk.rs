@@ -1,15 +1,74 @@
-use eframe::egui::{pos2, Color32, Pos2, Sense, Ui};
+use std::f64::consts::TAU;
+
+use eframe::egui::{Checkbox, Color32, Ui};
+use egui_plot::{Legend, Line, LineStyle, MarkerShape, Plot, PlotPoint, PlotPoints, Points};
+
+use crate::figure::{Figure, Panel, ToFigure};
+
+const SHANNON: Color32 = Color32::GREEN;
+const KULLBACK: Color32 = Color32::RED;
+const SELF: Color32 = Color32::from_rgb(31, 119, 180);
+const CLOSEST: Color32 = Color32::from_rgb(255, 127, 14);
+
+/// Realization placed on the plane by its distances to the centres of both classes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Realization {
+    /// Index of the realization in its class.
+    pub index: usize,
+    pub position: [f64; 2],
+    /// Distance to the centre of the class.
+    pub to_self: u32,
+    /// Distance to the centre of the closest class.
+    pub to_closest: u32,
+}
+
+/// Layers of the 2D view that can be hidden.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Layers {
+    pub self_realizations: bool,
+    pub closest_realizations: bool,
+    pub shannon: bool,
+    pub kullback: bool,
+    /// Containers of the closest class.
+    pub closest_containers: bool,
+}
+
+impl Default for Layers {
+    fn default() -> Self {
+        Self {
+            self_realizations: true,
+            closest_realizations: true,
+            shannon: true,
+            kullback: true,
+            closest_containers: true,
+        }
+    }
+}
+
+impl Layers {
+    pub fn show(&mut self, ui: &mut Ui) {
+        ui.horizontal_wrapped(|ui| {
+            ui.add(Checkbox::new(&mut self.self_realizations, "Realizations"));
+            ui.add(Checkbox::new(
+                &mut self.closest_realizations,
+                "Neighbour realizations",
+            ));
+            ui.add(Checkbox::new(&mut self.shannon, "Shannon"));
+            ui.add(Checkbox::new(&mut self.kullback, "Kullback"));
+            ui.add(Checkbox::new(
+                &mut self.closest_containers,
+                "Neighbour containers",
+            ));
+        });
+    }
+}
 
 #[derive(Debug, Default)]
 pub struct SK {
-    pub distances_to_self: Vec<u32>,
-    pub distances_to_closest: Vec<u32>,
-    pub distances_from_closest_to_itself: Vec<u32>,
-    pub distances_from_closest: Vec<u32>,
     pub distance: u32,
     pub closest: usize,
-    self_realizations: Vec<Pos2>,
-    closest_realizations: Vec<Pos2>,
+    self_realizations: Vec<Realization>,
+    closest_realizations: Vec<Realization>,
     r_kullback: Vec<f64>,
     r_shannon: Vec<f64>,
     r_kullback_closest: Vec<f64>,
@@ -18,27 +77,24 @@ pub struct SK {
 
 impl SK {
     pub fn new(
-        distances_to_self: Vec<u32>,
-        distances_to_closest: Vec<u32>,
-        distances_from_closest_to_itself: Vec<u32>,
-        distances_from_closest: Vec<u32>,
+        distances_to_self: &[u32],
+        distances_to_closest: &[u32],
+        distances_from_closest_to_itself: &[u32],
+        distances_from_closest: &[u32],
         distance: u32,
         closest: usize,
     ) -> Self {
         let self_realizations =
-            Self::calculate_coordinates(&distances_to_self, &distances_from_closest, distance);
+            Self::calculate_coordinates(distances_to_self, distances_from_closest, distance, 1.0);
 
         let closest_realizations = Self::calculate_coordinates(
-            &distances_to_closest,
-            &distances_from_closest_to_itself,
+            distances_to_closest,
+            distances_from_closest_to_itself,
             distance,
+            -1.0,
         );
 
         Self {
-            distances_to_self,
-            distances_to_closest,
-            distances_from_closest_to_itself,
-            distances_from_closest,
             distance,
             closest,
             self_realizations,
@@ -57,181 +113,245 @@ impl SK {
         self.r_shannon_closest = r_shannon;
     }
 
+    /// Places realizations by their distances to both centres, the centre of the class at
+    /// the origin and the centre of the closest class at `distance` on the x axis.
+    ///
+    /// Distances do not tell on which side of the axis a realization lies,
+    /// so realizations are put on the side given by the sign of `side`.
     fn calculate_coordinates(
         distances_to_self: &[u32],
         distances_from_closest: &[u32],
         distance: u32,
-    ) -> Vec<Pos2> {
+        side: f64,
+    ) -> Vec<Realization> {
         if distance == 0 {
             return Vec::new();
         }
 
-        let mut coordinates: Vec<Pos2> = Vec::with_capacity(distances_to_self.len());
-
-        for i in 0..distances_to_self.len() {
-            let distance = distance as f32;
-
-            let rs = distances_to_self[i] as f32; // Disctance to center of self class
-            let rc = distances_from_closest[i] as f32; // Distance to center of closest class
+        let distance = distance as f64;
 
-            let x = (distance.powi(2) - rc.powi(2) + rs.powi(2)) / (2.0 * distance);
-            let y2 = rs.powi(2) - x.powi(2);
-            let y = (y2 as f64).sqrt() as f32 * if i % 2 == 0 { 1.0 } else { -1.0 };
-
-            if y.is_finite() && x.is_finite() {
-                let center = pos2(x, y);
-                coordinates.push(center);
-            }
-        }
-
-        coordinates
+        distances_to_self
+            .iter()
+            .zip(distances_from_closest)
+            .enumerate()
+            .filter_map(|(index, (&to_self, &to_closest))| {
+                let rs = to_self as f64;
+                let rc = to_closest as f64;
+
+                let x = (distance.powi(2) - rc.powi(2) + rs.powi(2)) / (2.0 * distance);
+                let y = (rs.powi(2) - x.powi(2)).sqrt() * side;
+
+                (x.is_finite() && y.is_finite()).then_some(Realization {
+                    index,
+                    position: [x, y],
+                    to_self,
+                    to_closest,
+                })
+            })
+            .collect()
     }
 
-    pub fn paint(&self, ui: &mut Ui) {
+    /// Shows the classes and containers on an interactive plot.
+    pub fn show(&self, layers: &mut Layers, ui: &mut Ui) {
         if self.distance == 0 {
             ui.label("Nothing to show");
             return;
         }
 
-        let ((min_x, min_y), (max_x, max_y)) = self.find_min_max();
+        layers.show(ui);
+
+        let distance = self.distance as f64;
+        let closest = self.closest;
+        let self_realizations = self.self_realizations.clone();
+        let closest_realizations = self.closest_realizations.clone();
+
+        Plot::new(ui.id().with("2D"))
+            .legend(Legend::default())
+            .data_aspect(1.0)
+            .label_formatter(move |name, point| {
+                let realizations = match name {
+                    "Realizations" => &self_realizations,
+                    "Neighbour realizations" => &closest_realizations,
+                    _ => return name.to_owned(),
+                };
+
+                nearest(realizations, point).map_or(String::new(), |r| {
+                    format!(
+                        "{name} {}\nTo own centre: {}\nTo centre of class {closest}: {}",
+                        r.index, r.to_self, r.to_closest
+                    )
+                })
+            })
+            .show(ui, |ui| {
+                ui.points(
+                    Points::new(vec![[0.0, 0.0]])
+                        .name("Centre")
+                        .shape(MarkerShape::Diamond)
+                        .radius(5.0)
+                        .color(SELF),
+                );
+                ui.points(
+                    Points::new(vec![[distance, 0.0]])
+                        .name(format!("Centre of class {closest}"))
+                        .shape(MarkerShape::Diamond)
+                        .radius(5.0)
+                        .color(CLOSEST),
+                );
+
+                let mut containers = Vec::new();
+                if layers.shannon {
+                    containers.push(("Shannon", &self.r_shannon, 0.0, SHANNON, false));
+                }
+                if layers.kullback {
+                    containers.push(("Kullback", &self.r_kullback, 0.0, KULLBACK, false));
+                }
+                if layers.closest_containers && layers.shannon {
+                    let radii = &self.r_shannon_closest;
+                    containers.push(("Shannon", radii, distance, SHANNON, true));
+                }
+                if layers.closest_containers && layers.kullback {
+                    let radii = &self.r_kullback_closest;
+                    containers.push(("Kullback", radii, distance, KULLBACK, true));
+                }
+
+                for (criterion, radii, x, color, neighbour) in containers {
+                    for &r in radii {
+                        let (name, style) = if neighbour {
+                            (
+                                format!("{criterion} radius of class {closest}"),
+                                LineStyle::dashed_loose(),
+                            )
+                        } else {
+                            (format!("{criterion} radius"), LineStyle::Solid)
+                        };
+
+                        ui.line(
+                            Line::new(circle([x, 0.0], r))
+                                .name(name)
+                                .color(color)
+                                .style(style),
+                        );
+                    }
+                }
+
+                let realizations = [
+                    (
+                        layers.self_realizations,
+                        "Realizations",
+                        &self.self_realizations,
+                        SELF,
+                    ),
+                    (
+                        layers.closest_realizations,
+                        "Neighbour realizations",
+                        &self.closest_realizations,
+                        CLOSEST,
+                    ),
+                ];
+
+                for (visible, name, realizations, color) in realizations {
+                    if visible {
+                        ui.points(
+                            Points::new(
+                                realizations
+                                    .iter()
+                                    .map(|r| r.position)
+                                    .collect::<Vec<[f64; 2]>>(),
+                            )
+                            .name(name)
+                            .radius(2.5)
+                            .color(color),
+                        );
+                    }
+                }
+            });
+    }
+}
 
-        let size = ui.available_size();
-        let (response, painter) = ui.allocate_painter(size, Sense::hover());
-        let rect = response.rect;
-        let left_top = rect.left_top();
+/// Returns the realization closest to the point.
+fn nearest<'a>(realizations: &'a [Realization], point: &PlotPoint) -> Option<&'a Realization> {
+    let distance = |r: &Realization| (r.position[0] - point.x).hypot(r.position[1] - point.y);
 
-        let k = std::cmp::min_by(
-            size.x / (max_x - min_x + 15.0),
-            size.y / (max_y - min_y + 15.0),
-            |a, b| a.total_cmp(b),
-        );
+    realizations
+        .iter()
+        .min_by(|a, b| distance(a).total_cmp(&distance(b)))
+}
 
-        let radius = 1.0 * k;
-        let stroke_width = 1.0;
-        let padding_y = left_top.y + radius + stroke_width;
-        let padding_x = left_top.x + radius + stroke_width;
+fn circle(center: [f64; 2], radius: f64) -> PlotPoints {
+    PlotPoints::from_parametric_callback(
+        |t| {
+            (
+                center[0] + radius * (t * TAU).cos(),
+                center[1] + radius * (t * TAU).sin(),
+            )
+        },
+        0.0..=1.0,
+        100,
+    )
+}
 
-        let center = pos2(padding_x - min_x * k, padding_y - min_y * k);
-        painter.circle_filled(center, radius, Color32::GREEN);
+impl ToFigure for SK {
+    fn figure(&self) -> Figure {
+        let distance = self.distance as f64;
+        let mut panel = Panel::new(format!("Distance to closest class {}", self.closest))
+            .equal_aspect()
+            .circle([0.0, 0.0], 1.0, SELF, true)
+            .circle([distance, 0.0], 1.0, CLOSEST, true);
+
+        let radii = [
+            (&self.r_shannon, 0.0, SHANNON),
+            (&self.r_kullback, 0.0, KULLBACK),
+            (&self.r_shannon_closest, distance, SHANNON),
+            (&self.r_kullback_closest, distance, KULLBACK),
+        ];
+
+        for (radii, x, color) in radii {
+            for &r in radii {
+                panel = panel.circle([x, 0.0], r, color, false);
+            }
+        }
 
-        let center = pos2(
-            padding_x + (self.distance as f32 - min_x) * k,
-            padding_y - min_y * k,
-        );
-        painter.circle_filled(center, radius, Color32::RED);
+        let realizations = [
+            (&self.self_realizations, SELF),
+            (&self.closest_realizations, CLOSEST),
+        ];
 
-        self.r_shannon.iter().for_each(|r| {
-            let center = pos2(padding_x - min_x * k, padding_y - min_y * k);
-            painter.circle_stroke(center, *r as f32 * k, (stroke_width, Color32::GREEN));
-        });
+        for (realizations, color) in realizations {
+            for r in realizations {
+                panel = panel.circle(r.position, 1.0, color, false);
+            }
+        }
 
-        self.r_kullback.iter().for_each(|r| {
-            let center = pos2(padding_x - min_x * k, padding_y - min_y * k);
-            painter.circle_stroke(center, *r as f32 * k, (stroke_width, Color32::RED));
-        });
+        Figure::new(vec![panel])
+    }
+}
 
-        self.r_shannon_closest.iter().for_each(|r| {
-            let center = pos2(
-                padding_x - (min_x - self.distance as f32) * k,
-                padding_y - min_y * k,
-            );
-            painter.circle_stroke(center, *r as f32 * k, (stroke_width, Color32::GREEN));
-        });
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        self.r_kullback_closest.iter().for_each(|r| {
-            let center = pos2(
-                padding_x - (min_x - self.distance as f32) * k,
-                padding_y - min_y * k,
-            );
-            painter.circle_stroke(center, *r as f32 * k, (stroke_width, Color32::RED));
-        });
+    #[test]
+    fn realizations_are_placed_by_distances_to_both_centres() {
+        // Centres 10 apart, a realization 6 from its centre and 8 from the other one
+        let sk = SK::new(&[6, 20], &[8], &[6], &[8, 1], 10, 1);
 
-        self.self_realizations.iter().for_each(|c| {
-            let center = pos2(padding_x + (c.x - min_x) * k, padding_y + (c.y - min_y) * k);
-            painter.circle_stroke(center, radius, (stroke_width, Color32::GREEN));
-        });
+        assert_eq!(sk.self_realizations.len(), 1);
+        let r = sk.self_realizations[0];
+        assert_eq!(r.index, 0);
+        assert!((r.position[0] - 3.6).abs() < 1e-9);
+        assert!((r.position[1] - 4.8).abs() < 1e-9);
 
-        self.closest_realizations.iter().for_each(|c| {
-            let center = pos2(padding_x + (c.x - min_x) * k, padding_y + (c.y - min_y) * k);
-            painter.circle_stroke(center, radius, (stroke_width, Color32::RED));
-        });
+        // Realizations of the closest class are drawn below the axis
+        assert!((sk.closest_realizations[0].position[1] + 4.8).abs() < 1e-9);
     }
 
-    fn find_min_max(&self) -> ((f32, f32), (f32, f32)) {
-        let max_radius = self
-            .r_shannon
-            .iter()
-            .cloned()
-            .reduce(f64::max)
-            .unwrap_or_default()
-            .max(
-                self.r_kullback
-                    .iter()
-                    .cloned()
-                    .reduce(f64::max)
-                    .unwrap_or_default(),
-            ) as f32;
-
-        let max_radius_closest = self
-            .r_shannon_closest
-            .iter()
-            .cloned()
-            .reduce(f64::max)
-            .unwrap_or_default()
-            .max(
-                self.r_kullback_closest
-                    .iter()
-                    .cloned()
-                    .reduce(f64::max)
-                    .unwrap_or_default(),
-            ) as f32;
-
-        let min_x = (0..self.self_realizations.len())
-            .map(|i| {
-                self.self_realizations[i]
-                    .x
-                    .min(self.closest_realizations[i].x)
-            })
-            .reduce(f32::min)
-            .unwrap_or_default()
-            .min(0.0)
-            .min(max_radius * -1.0);
-
-        let min_y = (0..self.self_realizations.len())
-            .map(|i| {
-                self.self_realizations[i]
-                    .y
-                    .min(self.closest_realizations[i].y)
-            })
-            .reduce(f32::min)
-            .unwrap_or_default()
-            .min(0.0)
-            .min(max_radius * -1.0)
-            .min(max_radius_closest * -1.0);
-
-        let max_x = (0..self.self_realizations.len())
-            .map(|i| {
-                self.self_realizations[i]
-                    .x
-                    .max(self.closest_realizations[i].x)
-            })
-            .reduce(f32::max)
-            .unwrap_or_default()
-            .max(self.distance as f32)
-            .max(max_radius)
-            .max(max_radius_closest + self.distance as f32);
-
-        let max_y = (0..self.self_realizations.len())
-            .map(|i| {
-                self.self_realizations[i]
-                    .y
-                    .max(self.closest_realizations[i].y)
-            })
-            .reduce(f32::max)
-            .unwrap_or_default()
-            .max(0.0)
-            .max(max_radius);
+    #[test]
+    fn hovered_point_is_matched_to_nearest_realization() {
+        let sk = SK::new(&[6, 6], &[], &[], &[8, 6], 10, 1);
+
+        let hovered = nearest(&sk.self_realizations, &PlotPoint::new(5.0, 3.0)).unwrap();
 
-        ((min_x, min_y), (max_x, max_y))
+        assert_eq!(hovered.index, 1);
+        assert_eq!((hovered.to_self, hovered.to_closest), (6, 6));
     }
 }
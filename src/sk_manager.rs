@@ -1,4 +1,10 @@
-use crate::{class_data::TextureData, sk::SK};
+use crate::{
+    class_data::TextureData,
+    error::Error,
+    shape,
+    sk::SK,
+    table::{Table, ToTables},
+};
 
 #[derive(Default)]
 pub struct SKManager {
@@ -8,29 +14,55 @@ pub struct SKManager {
 }
 
 impl SKManager {
-    pub fn new(matrices: &[TextureData], reference_vectors: &[TextureData]) -> SKManager {
+    /// Calculates distances between classes and their realizations,
+    /// skipping `excluded` attributes.
+    ///
+    /// Fails if matrices or reference vectors do not have `attributes` attributes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are not as many matrices as reference vectors.
+    pub fn new(
+        matrices: &[TextureData],
+        reference_vectors: &[TextureData],
+        attributes: usize,
+        excluded: &[usize],
+    ) -> Result<SKManager, Error> {
+        assert_eq!(matrices.len(), reference_vectors.len());
+        shape::check_attributes(matrices, reference_vectors, attributes)?;
+        matrices
+            .iter()
+            .for_each(|matrix| matrix.shape().validate(&matrix.bytes));
+
+        let included = |bytes: &[u8]| -> Vec<u8> {
+            bytes
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !excluded.contains(&i.rem_euclid(attributes)))
+                .map(|(_, &byte)| byte)
+                .collect()
+        };
+        let matrices: Vec<Vec<u8>> = matrices.iter().map(|m| included(&m.bytes)).collect();
+        let reference_vectors: Vec<Vec<u8>> = reference_vectors
+            .iter()
+            .map(|v| included(&v.bytes))
+            .collect();
+
         let distances: Vec<Vec<u32>> = (0..matrices.len())
             .map(|i| {
                 (0..matrices.len())
-                    .map(|j| {
-                        Self::distance_between(
-                            &reference_vectors[i].bytes,
-                            &reference_vectors[j].bytes,
-                        )
-                    })
+                    .map(|j| Self::distance_between(&reference_vectors[i], &reference_vectors[j]))
                     .collect()
             })
-            .collect();
+            .collect::<Result<_, _>>()?;
 
         let distances_to_realizations: Vec<Vec<Vec<u32>>> = (0..matrices.len())
             .map(|i| {
                 (0..matrices.len())
-                    .map(|j| {
-                        Self::distances_between(&matrices[j].bytes, &reference_vectors[i].bytes)
-                    })
+                    .map(|j| Self::distances_between(&matrices[j], &reference_vectors[i]))
                     .collect()
             })
-            .collect();
+            .collect::<Result<_, _>>()?;
 
         let sk = (0..matrices.len())
             .map(|i| {
@@ -42,30 +74,34 @@ impl SKManager {
                     .unwrap_or_default();
 
                 SK::new(
-                    distances_to_center.to_vec(),
-                    distances_to_realizations[i][closest].clone(),
-                    distances_to_realizations[closest][closest].clone(),
-                    distances_to_realizations[closest][i].clone(),
+                    distances_to_center,
+                    &distances_to_realizations[i][closest],
+                    &distances_to_realizations[closest][closest],
+                    &distances_to_realizations[closest][i],
                     distances[i][closest],
                     closest,
                 )
             })
             .collect();
 
-        Self {
+        Ok(Self {
             sk,
             distances,
             distances_to_realizations,
-        }
+        })
     }
 
     /// Returns the Hamming distance between two vectors.
     ///
-    /// # Panics
-    ///
-    /// Panics if vectors have different lengths.
-    pub fn distance_between(vector1: &[u8], vector2: &[u8]) -> u32 {
-        assert_eq!(vector1.len(), vector2.len());
+    /// Fails if vectors have different lengths.
+    pub fn distance_between(vector1: &[u8], vector2: &[u8]) -> Result<u32, Error> {
+        if vector1.len() != vector2.len() {
+            return Err(Error::ShapeMismatch {
+                expected: vector1.len(),
+                got: vector2.len(),
+            });
+        }
+
         let mut sum: u32 = 0;
         for i in 0..vector1.len() {
             if vector2[i] != vector1[i] {
@@ -73,14 +109,139 @@ impl SKManager {
             }
         }
 
-        sum
+        Ok(sum)
     }
 
     /// Returns the vector of Hamming distances between each realization and vector.
-    pub fn distances_between(realizations: &[u8], center: &[u8]) -> Vec<u32> {
+    ///
+    /// Fails if the vector has no attributes.
+    pub fn distances_between(realizations: &[u8], center: &[u8]) -> Result<Vec<u32>, Error> {
+        if center.is_empty() {
+            return Err(Error::NoAttributes);
+        }
+
         realizations
             .chunks(center.len())
-            .map(|realization| Self::distance_between(realization, center))
+            .map(|realization| Self::distance_between(center, realization))
             .collect()
     }
 }
+
+impl ToTables for SKManager {
+    fn tables(&self) -> Vec<Table> {
+        let mut distances = Table::indexed("distances", "class", self.distances.len());
+
+        self.distances.iter().enumerate().for_each(|(class, row)| {
+            distances.push(
+                std::iter::once(class.into())
+                    .chain(row.iter().map(|&d| d.into()))
+                    .collect(),
+            );
+        });
+
+        let mut distances_to_realizations = Table::new(
+            "distances_to_realizations",
+            &["center", "class", "realization", "distance"],
+        );
+
+        self.distances_to_realizations
+            .iter()
+            .enumerate()
+            .for_each(|(center, classes)| {
+                classes
+                    .iter()
+                    .enumerate()
+                    .for_each(|(class, realizations)| {
+                        realizations
+                            .iter()
+                            .enumerate()
+                            .for_each(|(realization, &distance)| {
+                                distances_to_realizations.push(vec![
+                                    center.into(),
+                                    class.into(),
+                                    realization.into(),
+                                    distance.into(),
+                                ]);
+                            });
+                    });
+            });
+
+        vec![distances, distances_to_realizations]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn vectors(len: usize) -> impl Strategy<Value = Vec<u8>> {
+        proptest::collection::vec(prop_oneof![Just(u8::MIN), Just(u8::MAX)], len)
+    }
+
+    fn pair() -> impl Strategy<Value = (Vec<u8>, Vec<u8>)> {
+        (0..64usize).prop_flat_map(|len| (vectors(len), vectors(len)))
+    }
+
+    fn triple() -> impl Strategy<Value = (Vec<u8>, Vec<u8>, Vec<u8>)> {
+        (0..64usize).prop_flat_map(|len| (vectors(len), vectors(len), vectors(len)))
+    }
+
+    proptest! {
+        #[test]
+        fn distance_is_symmetric((a, b) in pair()) {
+            prop_assert_eq!(
+                SKManager::distance_between(&a, &b),
+                SKManager::distance_between(&b, &a)
+            );
+        }
+
+        #[test]
+        fn distance_to_itself_is_zero(a in vectors(64)) {
+            prop_assert_eq!(SKManager::distance_between(&a, &a), Ok(0));
+        }
+
+        #[test]
+        fn distance_is_bounded_by_length((a, b) in pair()) {
+            prop_assert!(SKManager::distance_between(&a, &b).unwrap() as usize <= a.len());
+        }
+
+        #[test]
+        fn distance_satisfies_triangle_inequality((a, b, c) in triple()) {
+            prop_assert!(
+                SKManager::distance_between(&a, &c).unwrap()
+                    <= SKManager::distance_between(&a, &b).unwrap()
+                        + SKManager::distance_between(&b, &c).unwrap()
+            );
+        }
+
+        #[test]
+        fn distances_are_calculated_per_realization((a, b) in pair(), realizations in 1..8usize) {
+            let matrix = a.repeat(realizations);
+            let distance = SKManager::distance_between(&a, &b).unwrap();
+
+            if !b.is_empty() {
+                prop_assert_eq!(
+                    SKManager::distances_between(&matrix, &b),
+                    Ok(vec![distance; realizations])
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn vectors_of_different_length_are_rejected() {
+        assert_eq!(
+            SKManager::distance_between(&[0, 255], &[0]),
+            Err(Error::ShapeMismatch {
+                expected: 2,
+                got: 1
+            })
+        );
+        assert!(SKManager::distances_between(&[0, 255, 0], &[0, 255]).is_err());
+        assert_eq!(
+            SKManager::distances_between(&[0, 255], &[]),
+            Err(Error::NoAttributes)
+        );
+    }
+}
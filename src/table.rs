@@ -0,0 +1,370 @@
+use std::{fmt::Write, path::Path};
+
+use eframe::egui::{Button, Label, RadioButton, TextEdit, Ui};
+
+use crate::error::Error;
+
+/// A value of a table cell.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cell {
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    Text(String),
+    /// Missing value.
+    Empty,
+}
+
+impl From<usize> for Cell {
+    fn from(value: usize) -> Self {
+        Self::Int(value as i64)
+    }
+}
+
+impl From<u32> for Cell {
+    fn from(value: u32) -> Self {
+        Self::Int(value as i64)
+    }
+}
+
+impl From<u8> for Cell {
+    fn from(value: u8) -> Self {
+        Self::Int(value as i64)
+    }
+}
+
+impl From<f64> for Cell {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<bool> for Cell {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<String> for Cell {
+    fn from(value: String) -> Self {
+        Self::Text(value)
+    }
+}
+
+impl<T: Into<Cell>> From<Option<T>> for Cell {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Empty, Into::into)
+    }
+}
+
+impl Cell {
+    fn to_csv(&self) -> String {
+        match self {
+            Cell::Int(value) => value.to_string(),
+            Cell::Float(value) => value.to_string(),
+            Cell::Bool(value) => value.to_string(),
+            Cell::Text(value) if value.contains([',', '"', '\n']) => {
+                format!("\"{}\"", value.replace('"', "\"\""))
+            }
+            Cell::Text(value) => value.clone(),
+            Cell::Empty => String::new(),
+        }
+    }
+
+    fn to_markdown(&self) -> String {
+        match self {
+            Cell::Text(value) => value.replace('|', "\\|"),
+            cell => cell.to_csv(),
+        }
+    }
+
+    /// Non-finite numbers have no JSON representation and are written as `null`.
+    fn to_json(&self) -> String {
+        match self {
+            Cell::Int(value) => value.to_string(),
+            Cell::Float(value) if value.is_finite() => format!("{value:?}"),
+            Cell::Float(_) | Cell::Empty => "null".to_owned(),
+            Cell::Bool(value) => value.to_string(),
+            Cell::Text(value) => json_string(value),
+        }
+    }
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+
+    value.chars().for_each(|c| match c {
+        '"' => escaped.push_str("\\\""),
+        '\\' => escaped.push_str("\\\\"),
+        '\n' => escaped.push_str("\\n"),
+        c if c.is_control() => {
+            let _ = write!(escaped, "\\u{:04x}", c as u32);
+        }
+        c => escaped.push(c),
+    });
+
+    escaped.push('"');
+    escaped
+}
+
+/// Named table of numeric results.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Cell>>,
+}
+
+impl Table {
+    pub fn new(name: impl Into<String>, columns: &[&str]) -> Self {
+        Self {
+            name: name.into(),
+            columns: columns.iter().map(|c| c.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// Creates a table with a column per value, named by its index.
+    pub fn indexed(name: impl Into<String>, first: &str, len: usize) -> Self {
+        Self {
+            name: name.into(),
+            columns: std::iter::once(first.to_owned())
+                .chain((0..len).map(|i| i.to_string()))
+                .collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// # Panics
+    ///
+    /// Panics if the row has a number of cells other than the number of columns.
+    pub fn push(&mut self, row: Vec<Cell>) {
+        assert_eq!(row.len(), self.columns.len());
+        self.rows.push(row);
+    }
+
+    pub fn to_csv(&self) -> String {
+        let header = self
+            .columns
+            .iter()
+            .map(|column| Cell::Text(column.clone()).to_csv())
+            .collect::<Vec<String>>()
+            .join(",");
+
+        std::iter::once(header)
+            .chain(self.rows.iter().map(|row| {
+                row.iter()
+                    .map(Cell::to_csv)
+                    .collect::<Vec<String>>()
+                    .join(",")
+            }))
+            .map(|line| line + "\n")
+            .collect()
+    }
+
+    /// Returns the table as a Markdown table with a header row.
+    pub fn to_markdown(&self) -> String {
+        let row = |cells: Vec<String>| format!("| {} |\n", cells.join(" | "));
+        let header = self
+            .columns
+            .iter()
+            .map(|column| Cell::Text(column.clone()).to_markdown())
+            .collect();
+
+        std::iter::once(row(header))
+            .chain(std::iter::once(row(vec![
+                "---".to_owned();
+                self.columns.len()
+            ])))
+            .chain(
+                self.rows
+                    .iter()
+                    .map(|cells| row(cells.iter().map(Cell::to_markdown).collect())),
+            )
+            .collect()
+    }
+
+    /// Returns the table as a JSON array with an object per row.
+    pub fn to_json(&self) -> String {
+        let rows: Vec<String> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let fields: Vec<String> = self
+                    .columns
+                    .iter()
+                    .zip(row)
+                    .map(|(column, cell)| format!("{}:{}", json_string(column), cell.to_json()))
+                    .collect();
+
+                format!("{{{}}}", fields.join(","))
+            })
+            .collect();
+
+        format!("[{}]\n", rows.join(",\n"))
+    }
+}
+
+/// Data that can be exported as tables.
+pub trait ToTables {
+    fn tables(&self) -> Vec<Table>;
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum DataFormat {
+    #[default]
+    Csv,
+    Json,
+}
+
+impl DataFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            DataFormat::Csv => "csv",
+            DataFormat::Json => "json",
+        }
+    }
+}
+
+impl std::str::FromStr for DataFormat {
+    type Err = Error;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value {
+            "csv" => Ok(Self::Csv),
+            "json" => Ok(Self::Json),
+            _ => Err(Error::InvalidArgument(format!(
+                "Unknown format {value}, expected csv or json"
+            ))),
+        }
+    }
+}
+
+/// Writes every table to `dir` as a file named after the table.
+pub fn save(tables: &[Table], dir: &Path, format: DataFormat) -> Result<(), Error> {
+    std::fs::create_dir_all(dir)
+        .map_err(|err| Error::Export(format!("{}: {err}", dir.display())))?;
+
+    tables.iter().try_for_each(|table| {
+        let contents = match format {
+            DataFormat::Csv => table.to_csv(),
+            DataFormat::Json => table.to_json(),
+        };
+        let path = dir.join(format!("{}.{}", table.name, format.extension()));
+
+        std::fs::write(&path, contents)
+            .map_err(|err| Error::Export(format!("{}: {err}", path.display())))
+    })
+}
+
+/// Controls for saving all numeric results to a directory.
+#[derive(Default)]
+pub struct DataExport {
+    path: String,
+    format: DataFormat,
+    message: Option<String>,
+}
+
+impl DataExport {
+    /// Shows the controls and returns `true` when export is requested.
+    pub fn show(&mut self, ui: &mut Ui) -> bool {
+        let mut clicked = false;
+
+        ui.horizontal(|ui| {
+            ui.add(TextEdit::singleline(&mut self.path).hint_text("Directory"));
+
+            [DataFormat::Csv, DataFormat::Json]
+                .iter()
+                .for_each(|&format| {
+                    if ui
+                        .add(RadioButton::new(
+                            self.format == format,
+                            format.extension().to_uppercase(),
+                        ))
+                        .clicked()
+                    {
+                        self.format = format;
+                    }
+                });
+
+            clicked = ui.add(Button::new("Export data")).clicked();
+
+            if let Some(message) = self.message.clone() {
+                ui.add(Label::new(message));
+
+                if ui.add(Button::new("x")).clicked() {
+                    self.message = None;
+                }
+            }
+        });
+
+        clicked
+    }
+
+    pub fn export(&mut self, tables: &[Table]) {
+        self.message = Some(match save(tables, Path::new(&self.path), self.format) {
+            Ok(()) => format!("Saved to {}", self.path),
+            Err(err) => err.to_string(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> Table {
+        let mut table = Table::new("test", &["radius", "d1", "name", "in"]);
+        table.push(vec![
+            1usize.into(),
+            0.5.into(),
+            "a, b".to_owned().into(),
+            true.into(),
+        ]);
+        table.push(vec![
+            2usize.into(),
+            f64::INFINITY.into(),
+            "\"q\"".to_owned().into(),
+            false.into(),
+        ]);
+        table
+    }
+
+    #[test]
+    fn csv_quotes_text_with_separators() {
+        assert_eq!(
+            table().to_csv(),
+            "radius,d1,name,in\n1,0.5,\"a, b\",true\n2,inf,\"\"\"q\"\"\",false\n"
+        );
+    }
+
+    #[test]
+    fn json_writes_non_finite_numbers_as_null() {
+        assert_eq!(
+            table().to_json(),
+            "[{\"radius\":1,\"d1\":0.5,\"name\":\"a, b\",\"in\":true},\n\
+             {\"radius\":2,\"d1\":null,\"name\":\"\\\"q\\\"\",\"in\":false}]\n"
+        );
+    }
+
+    #[test]
+    fn json_keeps_floats_distinct_from_integers() {
+        assert_eq!(Cell::Float(1.0).to_json(), "1.0");
+        assert_eq!(Cell::Int(1).to_json(), "1");
+    }
+
+    #[test]
+    #[should_panic]
+    fn row_not_matching_columns_panics() {
+        Table::new("test", &["a", "b"]).push(vec![1usize.into()]);
+    }
+
+    #[test]
+    fn indexed_table_names_columns_by_index() {
+        assert_eq!(
+            Table::indexed("test", "class", 2).columns,
+            ["class", "0", "1"]
+        );
+    }
+}
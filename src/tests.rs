@@ -0,0 +1,270 @@
+//! Golden regression tests of the training pipeline on the bundled classes.
+
+use eframe::egui::Context;
+
+use crate::{
+    class_loader::ClassType,
+    class_manager::ClassOperation,
+    criterion::CriterionKind,
+    error::Error,
+    exam_data::ExamResult,
+    fixtures,
+    optimizer::{Parameter, ParameterRange},
+    shape::Shape,
+    MyApp,
+};
+
+struct Golden {
+    delta: u8,
+    reference_vectors: [&'static str; 3],
+    distances: [[u32; 3]; 3],
+    r_kullback: [&'static [f64]; 3],
+    r_shannon: [&'static [f64]; 3],
+    exam: ExamResult,
+}
+
+/// Trains on `1.bmp`, `2.bmp` and `3.bmp` with the first class as base and examines `exm.bmp`.
+fn trained_app(delta: u8, ctx: &Context) -> MyApp {
+    let mut app = MyApp::default();
+    app.class_loader.shape = fixtures::SHAPE;
+
+    ["1.bmp", "2.bmp", "3.bmp"].iter().for_each(|name| {
+        app.add_class(fixtures::load(name, ctx), ctx).unwrap();
+    });
+
+    app.class_loader.class_type = ClassType::Exam;
+    app.add_class(fixtures::load("exm.bmp", ctx), ctx).unwrap();
+
+    app.delta = delta.into();
+    app.corridor.delta(app.delta);
+    app.recalculate(ctx);
+
+    app
+}
+
+fn assert_golden(golden: Golden) {
+    let ctx = Context::default();
+    let app = trained_app(golden.delta, &ctx);
+
+    let reference_vectors: Vec<String> = app
+        .class_manager
+        .binary_representations
+        .reference_vectors
+        .iter()
+        .map(|vector| fixtures::bits(&vector.bytes))
+        .collect();
+    assert_eq!(reference_vectors, golden.reference_vectors);
+
+    assert_eq!(app.sk_manager.distances, golden.distances);
+
+    let r_kullback: Vec<&[f64]> = app
+        .criterias
+        .iter()
+        .map(|c| c.radii(CriterionKind::Kullback))
+        .collect();
+    assert_eq!(r_kullback, golden.r_kullback);
+
+    let r_shannon: Vec<&[f64]> = app
+        .criterias
+        .iter()
+        .map(|c| c.radii(CriterionKind::Shannon))
+        .collect();
+    assert_eq!(r_shannon, golden.r_shannon);
+
+    assert_eq!(app.exam_data, vec![golden.exam]);
+}
+
+#[test]
+fn golden_delta_20() {
+    assert_golden(Golden {
+        delta: 20,
+        reference_vectors: [
+            "0000000000011111111110001000000000000000000000000000000011000000000000000000110000000001000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+            "0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+        ],
+        distances: [[0, 16, 16], [16, 0, 0], [16, 0, 0]],
+        r_kullback: [&[], &[], &[]],
+        r_shannon: [&[], &[], &[]],
+        exam: ExamResult::Unknown((vec![0, 0, 0], 100)),
+    });
+}
+
+#[test]
+fn golden_delta_40() {
+    assert_golden(Golden {
+        delta: 40,
+        reference_vectors: [
+            "1111111111111111111111111111111111111111111111111111111111111111111111111111111111111111111111111111",
+            "0000111000011110011111000110100000000000100000000000111111000001110110000000000010011111111110000000",
+            "0000000000000000000000000000000011111010011111110000000000000000000000000000000000000000000000000111",
+        ],
+        distances: [[0, 62, 84], [62, 0, 54], [84, 54, 0]],
+        r_kullback: [&[38.0], &[44.0], &[37.0]],
+        r_shannon: [&[42.0], &[44.0], &[37.0]],
+        exam: ExamResult::Found(1, (vec![9, 71, 0], 20)),
+    });
+}
+
+#[test]
+fn golden_delta_50() {
+    assert_golden(Golden {
+        delta: 50,
+        reference_vectors: [
+            "1111111111111111111111111111111111111111111111111111111111111111111111111111111111111111111111111111",
+            "0111111110111111111111111111111100000001100000000001111111111111111110000011110111111111111111000000",
+            "0000000000000010000000000000001111111111111111111110000000000000000000111111100000000000000000001111",
+        ],
+        distances: [[0, 31, 67], [31, 0, 86], [67, 86, 0]],
+        r_kullback: [&[27.0], &[], &[46.0, 47.0]],
+        r_shannon: [&[27.0], &[], &[46.0, 47.0]],
+        exam: ExamResult::Unknown((vec![16, 0, 0], 84)),
+    });
+}
+
+#[test]
+fn golden_delta_60() {
+    assert_golden(Golden {
+        delta: 60,
+        reference_vectors: [
+            "1111111111111111111111111111111111111111111111111111111111111111111111111111111111111111111111111111",
+            "1111111111111111111111111111111110000111110000110111111111111111111111000111111111111111111111000000",
+            "1000000011101111000000000001111111111111111111111111000000000000000001111111111000000000000000111111",
+        ],
+        distances: [[0, 18, 51], [18, 0, 69], [51, 69, 0]],
+        r_kullback: [&[14.0, 15.0], &[], &[45.0]],
+        r_shannon: [&[14.0, 15.0], &[], &[45.0]],
+        exam: ExamResult::Unknown((vec![6, 0, 3], 91)),
+    });
+}
+
+#[test]
+fn auto_base_class_selects_class_with_best_mean_criterion() {
+    let ctx = Context::default();
+    let mut app = trained_app(0, &ctx);
+    app.settings.delta_range = ParameterRange::new(Parameter::Delta, 35.0, 65.0, 1.0);
+
+    app.auto_base_class(&ctx);
+
+    assert_eq!(app.class_manager.selected_class, 2);
+    assert_eq!(app.delta, 61.0);
+    assert_eq!(
+        app.base_comparison
+            .as_ref()
+            .map(|comparison| comparison.candidates.len()),
+        Some(3)
+    );
+}
+
+#[test]
+fn undo_restores_base_class_and_delta() {
+    let ctx = Context::default();
+    let mut app = trained_app(40, &ctx);
+    let distances = app.sk_manager.distances.clone();
+    app.settings.delta_range = ParameterRange::new(Parameter::Delta, 35.0, 65.0, 1.0);
+
+    app.auto_base_class(&ctx);
+    assert_ne!(app.delta, 40.0);
+
+    app.undo(&ctx);
+    assert_eq!(app.class_manager.selected_class, 0);
+    assert_eq!(app.delta, 40.0);
+    assert_eq!(app.sk_manager.distances, distances);
+
+    app.redo(&ctx);
+    assert_eq!(app.class_manager.selected_class, 2);
+
+    // Back past the auto base class and the loads of the exam class and the last class
+    app.undo(&ctx);
+    app.undo(&ctx);
+    assert!(app.exam_class_manager.classes.is_empty());
+    app.undo(&ctx);
+    assert_eq!(app.class_manager.classes.len(), 2);
+}
+
+#[test]
+fn undo_restores_prior_weights_of_reordered_classes() {
+    let ctx = Context::default();
+    let mut app = trained_app(40, &ctx);
+    app.settings.prior_weights = vec![1.0, 2.0, 3.0];
+
+    app.restructure(ClassType::Training, ClassOperation::MoveUp(2), &ctx)
+        .unwrap();
+    assert_eq!(app.settings.prior_weights, [1.0, 3.0, 2.0]);
+
+    app.undo(&ctx);
+    assert_eq!(app.settings.prior_weights, [1.0, 2.0, 3.0]);
+}
+
+#[test]
+fn classes_may_have_different_numbers_of_realizations() {
+    let ctx = Context::default();
+    let mut app = MyApp::default();
+    app.class_loader.shape = fixtures::SHAPE;
+    [
+        fixtures::load("1.bmp", &ctx),
+        fixtures::load_cropped("2.bmp", Shape::new(100, 60), &ctx),
+        fixtures::load("3.bmp", &ctx),
+    ]
+    .into_iter()
+    .for_each(|class| app.add_class(class, &ctx).unwrap());
+
+    app.delta = 40.0;
+    app.corridor.delta(app.delta);
+    app.recalculate(&ctx);
+
+    assert_eq!(app.class_manager.classes.len(), 3);
+    assert_eq!(app.sk_manager.distances_to_realizations[0][1].len(), 60);
+    assert!(app
+        .criterias
+        .iter()
+        .all(|criteria| criteria.characteristics.last().unwrap().d1 <= 1.0));
+    assert!(app.criterias[1]
+        .best_criteria(CriterionKind::Shannon)
+        .is_some());
+}
+
+#[test]
+fn classes_without_working_space_are_warned_about_and_not_recognized() {
+    let ctx = Context::default();
+    let mut app = trained_app(40, &ctx);
+
+    app.settings.working_space.max_radius = Some(0);
+    app.recalculate(&ctx);
+
+    assert_eq!(app.notifications.warnings().len(), 3);
+    assert!(app
+        .exam_data
+        .iter()
+        .all(|result| matches!(result, ExamResult::Unknown(_))));
+}
+
+#[test]
+fn rejected_class_keeps_the_corridor() {
+    let ctx = Context::default();
+    let mut app = MyApp::default();
+    app.class_loader.shape = fixtures::SHAPE;
+    app.class_loader.class_type = ClassType::Exam;
+    app.add_class(fixtures::load("exm.bmp", &ctx), &ctx)
+        .unwrap();
+    let lower = app.corridor.allowances.lower.clone();
+
+    let cropped = fixtures::load_cropped("1.bmp", Shape::new(50, 100), &ctx);
+    assert!(app.add_class(cropped, &ctx).is_err());
+
+    assert_eq!(app.corridor.allowances.lower, lower);
+}
+
+#[test]
+fn failed_training_skips_exam() {
+    let ctx = Context::default();
+    let mut app = trained_app(40, &ctx);
+
+    app.settings.excluded_attributes = (0..fixtures::SHAPE.attributes).collect();
+    app.recalculate(&ctx);
+
+    assert_eq!(app.notifications.errors(), [Error::NoAttributes]);
+    assert!(app.criterias.is_empty());
+    assert!(app.exam_data.is_empty());
+    assert_eq!(app.settings.prior_weights.len(), 3);
+}
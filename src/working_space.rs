@@ -0,0 +1,297 @@
+use std::ops::Range;
+
+use eframe::{
+    egui::{Checkbox, DragValue, Ui},
+    epaint::Color32,
+};
+
+use crate::{
+    criteria::Characteristics,
+    table::{Table, ToTables},
+};
+
+/// Constraints on radii of containers considered by criteria and delta optimization.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkingSpace {
+    pub min_d1: f64,
+    pub min_d2: f64,
+    pub max_alpha: Option<f64>,
+    pub max_beta: Option<f64>,
+    pub min_radius: usize,
+    /// Largest radius, radii below the distance to the closest class if not set.
+    pub max_radius: Option<usize>,
+}
+
+impl Default for WorkingSpace {
+    fn default() -> Self {
+        Self {
+            min_d1: 0.5,
+            min_d2: 0.5,
+            max_alpha: None,
+            max_beta: None,
+            min_radius: 1,
+            max_radius: None,
+        }
+    }
+}
+
+/// Radii excluded from working space by one of its constraints.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Region {
+    pub name: String,
+    pub color: Color32,
+    pub intervals: Vec<Range<usize>>,
+}
+
+/// Bound of characteristics with its name and color on plots.
+struct Constraint {
+    name: String,
+    color: Color32,
+    holds: fn(&WorkingSpace, &Characteristics) -> bool,
+}
+
+impl WorkingSpace {
+    /// Whether a container with these characteristics satisfies the bounds of characteristics.
+    pub fn contains(&self, c: &Characteristics) -> bool {
+        self.constraints()
+            .iter()
+            .all(|constraint| (constraint.holds)(self, c))
+    }
+
+    /// Radii allowed by the radius bounds for a class at `distance` from the closest class.
+    pub fn radii(&self, distance: u32) -> Range<usize> {
+        let end = self.max_radius.map_or(distance as usize, |max| max + 1);
+
+        self.min_radius..end.max(self.min_radius)
+    }
+
+    /// Radii of working space among radii with the given characteristics.
+    pub fn select(&self, characteristics: &[Characteristics], distance: u32) -> Vec<usize> {
+        let radii = self.radii(distance);
+
+        characteristics
+            .iter()
+            .enumerate()
+            .filter(|(i, c)| radii.contains(i) && self.contains(c))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// Returns radii excluded by every constraint, skipping constraints that exclude none.
+    pub fn regions(&self, characteristics: &[Characteristics], distance: u32) -> Vec<Region> {
+        let radii = self.radii(distance);
+        let mut regions = vec![Region {
+            name: "Radius bounds".to_owned(),
+            color: Color32::GRAY,
+            intervals: intervals((0..characteristics.len()).map(|i| !radii.contains(&i))),
+        }];
+
+        regions.extend(self.constraints().into_iter().map(|constraint| Region {
+            intervals: intervals(characteristics.iter().map(|c| !(constraint.holds)(self, c))),
+            name: constraint.name,
+            color: constraint.color,
+        }));
+
+        regions.retain(|region| !region.intervals.is_empty());
+        regions
+    }
+
+    fn constraints(&self) -> Vec<Constraint> {
+        let mut constraints = vec![
+            Constraint {
+                name: format!("D1 < {}", self.min_d1),
+                color: Color32::from_rgb(214, 39, 40),
+                holds: |s, c| c.d1 >= s.min_d1 && c.d1 <= 1.0,
+            },
+            Constraint {
+                name: format!("D2 < {}", self.min_d2),
+                color: Color32::from_rgb(148, 103, 189),
+                holds: |s, c| c.d2 >= s.min_d2 && c.d2 <= 1.0,
+            },
+        ];
+
+        if let Some(max_alpha) = self.max_alpha {
+            constraints.push(Constraint {
+                name: format!("Alpha > {max_alpha}"),
+                color: Color32::from_rgb(255, 127, 14),
+                holds: |s, c| s.max_alpha.is_none_or(|max| c.alpha <= max),
+            });
+        }
+
+        if let Some(max_beta) = self.max_beta {
+            constraints.push(Constraint {
+                name: format!("Beta > {max_beta}"),
+                color: Color32::from_rgb(140, 86, 75),
+                holds: |s, c| s.max_beta.is_none_or(|max| c.beta <= max),
+            });
+        }
+
+        constraints
+    }
+
+    /// Shows the controls and returns `true` if any bound has changed.
+    pub fn show(&mut self, ui: &mut Ui) -> bool {
+        let mut changed = false;
+
+        ui.horizontal(|ui| {
+            ui.label("Min D1");
+            changed |= ui.add(probability(&mut self.min_d1)).changed();
+            ui.label("Min D2");
+            changed |= ui.add(probability(&mut self.min_d2)).changed();
+        });
+
+        ui.horizontal(|ui| {
+            changed |= optional(ui, "Max alpha", &mut self.max_alpha, 0.5, probability);
+            changed |= optional(ui, "Max beta", &mut self.max_beta, 0.5, probability);
+        });
+
+        ui.horizontal(|ui| {
+            ui.label("Min radius");
+            changed |= ui.add(radius(&mut self.min_radius)).changed();
+            changed |= optional(ui, "Max radius", &mut self.max_radius, 100, radius);
+        });
+
+        changed
+    }
+}
+
+impl ToTables for WorkingSpace {
+    fn tables(&self) -> Vec<Table> {
+        let mut table = Table::new("working_space", &["parameter", "value"]);
+        let optional = |value: Option<f64>| value.map_or(String::new().into(), Into::into);
+
+        table.push(vec!["min_d1".to_owned().into(), self.min_d1.into()]);
+        table.push(vec!["min_d2".to_owned().into(), self.min_d2.into()]);
+        table.push(vec![
+            "max_alpha".to_owned().into(),
+            optional(self.max_alpha),
+        ]);
+        table.push(vec!["max_beta".to_owned().into(), optional(self.max_beta)]);
+        table.push(vec!["min_radius".to_owned().into(), self.min_radius.into()]);
+        table.push(vec![
+            "max_radius".to_owned().into(),
+            self.max_radius
+                .map_or(String::new().into(), |radius| radius.into()),
+        ]);
+
+        vec![table]
+    }
+}
+
+fn probability(value: &mut f64) -> DragValue<'_> {
+    DragValue::new(value).speed(0.01).clamp_range(0.0..=1.0)
+}
+
+fn radius(value: &mut usize) -> DragValue<'_> {
+    DragValue::new(value).clamp_range(0..=usize::MAX)
+}
+
+/// Shows a checkbox enabling the value and its editor, returns `true` if anything has changed.
+fn optional<T: Copy>(
+    ui: &mut Ui,
+    label: &str,
+    value: &mut Option<T>,
+    default: T,
+    editor: fn(&mut T) -> DragValue<'_>,
+) -> bool {
+    let mut enabled = value.is_some();
+    let mut changed = ui.add(Checkbox::new(&mut enabled, label)).changed();
+
+    match (enabled, value.is_some()) {
+        (true, false) => *value = Some(default),
+        (false, true) => *value = None,
+        _ => {}
+    }
+
+    if let Some(value) = value.as_mut() {
+        changed |= ui.add(editor(value)).changed();
+    }
+
+    changed
+}
+
+/// Groups consecutive indices where `excluded` is `true`.
+fn intervals(excluded: impl Iterator<Item = bool>) -> Vec<Range<usize>> {
+    let mut intervals: Vec<Range<usize>> = Vec::new();
+
+    excluded
+        .enumerate()
+        .filter(|(_, e)| *e)
+        .for_each(|(i, _)| match intervals.last_mut() {
+            Some(last) if last.end == i => last.end = i + 1,
+            _ => intervals.push(i..i + 1),
+        });
+
+    intervals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn characteristics(d1: f64, d2: f64) -> Characteristics {
+        Characteristics {
+            d1,
+            beta: 1.0 - d2,
+            alpha: 1.0 - d1,
+            d2,
+        }
+    }
+
+    #[test]
+    fn default_keeps_reliable_radii_below_distance() {
+        let characteristics = [
+            characteristics(0.9, 0.9),
+            characteristics(0.9, 0.9),
+            characteristics(0.4, 0.9),
+            characteristics(0.9, 0.9),
+            characteristics(0.9, 0.9),
+        ];
+
+        assert_eq!(WorkingSpace::default().select(&characteristics, 4), [1, 3]);
+    }
+
+    #[test]
+    fn optional_bounds_narrow_working_space() {
+        let working_space = WorkingSpace {
+            max_alpha: Some(0.2),
+            max_radius: Some(2),
+            ..Default::default()
+        };
+        let characteristics = [
+            characteristics(0.9, 0.9),
+            characteristics(0.7, 0.9),
+            characteristics(0.9, 0.9),
+            characteristics(0.9, 0.9),
+        ];
+
+        assert_eq!(working_space.select(&characteristics, 10), [2]);
+    }
+
+    #[test]
+    fn regions_group_excluded_radii() {
+        let characteristics = [
+            characteristics(0.9, 0.9),
+            characteristics(0.4, 0.9),
+            characteristics(0.4, 0.9),
+            characteristics(0.9, 0.9),
+            characteristics(0.9, 0.9),
+        ];
+
+        assert_eq!(
+            WorkingSpace::default()
+                .regions(&characteristics, 4)
+                .iter()
+                .map(|region| {
+                    let intervals: Vec<(usize, usize)> =
+                        region.intervals.iter().map(|r| (r.start, r.end)).collect();
+                    (region.name.as_str(), intervals)
+                })
+                .collect::<Vec<_>>(),
+            [
+                ("Radius bounds", vec![(0, 1), (4, 5)]),
+                ("D1 < 0.5", vec![(1, 3)])
+            ]
+        );
+    }
+}